@@ -0,0 +1,92 @@
+// Every instruction the CPU knows: its mnemonic, how many operands it takes,
+// how many cycles it needs, and what it does to the registers once those
+// cycles are over.
+pub struct Operation {
+    pub name: &'static str,
+    pub operands: usize,
+    pub cycles: usize,
+    pub execute: fn(&mut Registers, &[i32]),
+}
+
+pub const INSTRUCTION_TABLE: &[Operation] = &[
+    Operation {
+        name: "noop",
+        operands: 0,
+        cycles: 1,
+        execute: |_, _| {},
+    },
+    Operation {
+        name: "addx",
+        operands: 1,
+        cycles: 2,
+        execute: |registers, operands| registers.x += operands[0],
+    },
+];
+
+#[derive(Clone, Copy, Debug)]
+pub struct Registers {
+    pub x: i32,
+}
+
+pub struct Instruction {
+    pub operation: &'static Operation,
+    pub operands: Vec<i32>,
+}
+
+pub fn parse_program(input: &str) -> Vec<Instruction> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut words = line.split_whitespace();
+            let name = words.next().unwrap();
+            let operation = INSTRUCTION_TABLE
+                .iter()
+                .find(|o| o.name == name)
+                .unwrap_or_else(|| panic!("Unknown instruction {:?}", line));
+            let operands = words
+                .map(|w| {
+                    w.parse::<i32>()
+                        .unwrap_or_else(|_| panic!("Invalid operand {:?} in {:?}", w, line))
+                })
+                .collect::<Vec<i32>>();
+            if operands.len() != operation.operands {
+                panic!(
+                    "{} takes {} operands, got {:?}",
+                    name, operation.operands, line
+                );
+            }
+            Instruction {
+                operation,
+                operands,
+            }
+        })
+        .collect()
+}
+
+pub struct Cpu {
+    pub registers: Registers,
+    pub cycle: usize,
+}
+
+impl Cpu {
+    pub fn new() -> Self {
+        Cpu {
+            registers: Registers { x: 1 },
+            cycle: 0,
+        }
+    }
+
+    // Runs the whole program. `during` sees every cycle, counted from 1, with
+    // the registers as they are while that cycle is going on: an instruction
+    // only takes effect after its last cycle.
+    pub fn run(&mut self, program: &[Instruction], mut during: impl FnMut(usize, &Registers)) {
+        for instruction in program {
+            for _ in 0..instruction.operation.cycles {
+                self.cycle += 1;
+                during(self.cycle, &self.registers);
+            }
+            (instruction.operation.execute)(&mut self.registers, &instruction.operands);
+        }
+    }
+}
@@ -1,66 +1,78 @@
-use std::fs;
+use std::{env, fs};
 
-#[derive(Clone, Debug)]
-enum Instruction {
-    Noop, 
-    AddStart(i32),
-    AddEnd(i32)
-}
-
-fn main() {
-    let input = fs::read_to_string("./input.txt").expect("File not loaded");
-
-    let mut register_value: i32 = 1;
-    let mut val_on_cycle = vec![1; 240];
-    let mut display = vec![vec!['.'; 40]; 6];
+use common::ocr;
 
-    let mut instructions: Vec<Instruction> = input.lines().map(|line| {
-        if line == "noop" {
-            Instruction::Noop
-        } else {
-            let to_add: i32 = line[5..].parse().unwrap();
-            Instruction::AddStart(to_add)
-        }
-    }).collect();
+use crate::cpu::{parse_program, Cpu};
 
-    let mut current_instruction: Instruction = Instruction::Noop;
+mod cpu;
 
-    for i in 0..240 {
+const SCREEN: (usize, usize) = (40, 6);
 
-        match current_instruction {
-            Instruction::Noop => {
-                current_instruction = instructions[0].clone();
-                instructions.remove(0);
-            },
-            Instruction::AddStart(val) => {
-                current_instruction = Instruction::AddEnd(val);
-            },
-            Instruction::AddEnd(val) => {
-                register_value += val; 
-                current_instruction = instructions[0].clone();
-                instructions.remove(0);
-            }
-        };
-        
-        val_on_cycle[i] = register_value; 
+struct Screen {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec<char>>,
+}
 
-        let row = i / 40;
-        let pixel_position = i % 40;
+impl Screen {
+    fn new(width: usize, height: usize) -> Self {
+        Screen {
+            width,
+            height,
+            pixels: vec![vec!['.'; width]; height],
+        }
+    }
 
-        if ((register_value - 1)..=(register_value + 1)).contains(&(pixel_position as i32)) {
-        display[row][pixel_position] = '#';
+    // The beam draws one pixel per cycle, left to right and top to bottom. The
+    // pixel is lit when the three pixel wide sprite centred on `x` covers it.
+    // None once the beam has gone past the last row.
+    fn draw(&mut self, cycle: usize, x: i32) -> Option<char> {
+        let position = cycle - 1;
+        if position >= self.width * self.height {
+            return None;
+        }
+        let (row, column) = (position / self.width, position % self.width);
+        if (x - 1..=x + 1).contains(&(column as i32)) {
+            self.pixels[row][column] = '#';
         }
+        Some(self.pixels[row][column])
     }
+}
 
+fn main() {
+    let input = fs::read_to_string("./input.txt").expect("File not loaded");
+    let program = parse_program(&input);
+    let trace = env::args().any(|a| a == "--trace");
+
+    let mut screen = Screen::new(SCREEN.0, SCREEN.1);
     let mut result_1 = 0;
-    for cycle in [20, 60, 100, 140, 180, 220] {
-        let val = val_on_cycle[cycle - 1];
-        result_1 += val * (cycle as i32);
-    }
+    Cpu::new().run(&program, |cycle, registers| {
+        if [20, 60, 100, 140, 180, 220].contains(&cycle) {
+            result_1 += cycle as i32 * registers.x;
+        }
+        let pixel = screen.draw(cycle, registers.x);
+        if trace {
+            println!(
+                "cycle {:>4}  X {:>4}  pixel {}",
+                cycle,
+                registers.x,
+                pixel.unwrap_or(' ')
+            );
+        }
+    });
 
     println!("{}", result_1);
 
-    for row in display {
+    for row in screen.pixels.iter() {
         println!("{}", row.iter().collect::<String>());
     }
+
+    let reading = ocr::read_chars(&screen.pixels, '#');
+    for glyph in reading.unknown.iter() {
+        eprintln!("Unknown glyph at column {}:", glyph.column);
+        for row in glyph.rows.iter() {
+            eprintln!("{}", row);
+        }
+    }
+    println!("{}", reading.text);
 }
@@ -1,135 +1,142 @@
 use std::fs;
 
-#[derive(Clone, Copy, Debug)]
-enum Operator {
-    Add,
-    Multiply,
-}
+use common::{arg_value, parsed_arg};
+use num::{BigInt, Integer, Zero};
 
-#[derive(Clone, Copy, Debug)]
-enum OperatorValue {
-    Usize(usize),
-    OwnSelf,
-}
+use crate::notes::{parse_expr, parse_notes, Expr, Monkey, Operator};
+
+mod notes;
+
+// How worry levels are stored while monkeys throw items around.
+trait Arithmetic {
+    type Value: Clone;
 
-#[derive(Clone, Debug)]
-struct Monke {
-    items: Vec<usize>,
-    inspect_operation: (Operator, OperatorValue),
-    test_div: usize,
-    test_addr: (usize, usize),
-    inspect_count: usize,
+    fn number(&self, n: u64) -> Self::Value;
+    fn apply(&self, operator: Operator, left: Self::Value, right: Self::Value) -> Self::Value;
+    fn divisible(&self, value: &Self::Value, divisor: u64) -> bool;
 }
 
-fn monke_lines(input: String) -> Vec<Monke> {
-    let mut monkes_input: Vec<Vec<String>> = vec![vec![]];
-    let mut current_monke: usize = 0;
+// Worry levels modulo a multiple of every divisor. Every test still gives the
+// same answer, and the numbers never grow, but there's no way to divide.
+struct Modular(u64);
 
-    for line in input.lines() {
-        if line == "" {
-            current_monke += 1;
-            monkes_input.push(vec![]);
-        } else {
-            monkes_input[current_monke].push(String::from(line));
-        }
+impl Arithmetic for Modular {
+    type Value = u64;
+
+    fn number(&self, n: u64) -> u64 {
+        n % self.0
     }
 
-    monkes_input
-        .iter()
-        .map(|input| create_monke(input))
-        .collect()
-}
+    fn apply(&self, operator: Operator, left: u64, right: u64) -> u64 {
+        let (left, right, modulus) = (left as u128, right as u128, self.0 as u128);
+        let result = match operator {
+            Operator::Add => left + right,
+            Operator::Subtract => left + modulus - right,
+            Operator::Multiply => left * right,
+            Operator::Divide => unreachable!("Division in modular arithmetic"),
+        };
+        (result % modulus) as u64
+    }
 
-fn create_monke(input: &Vec<String>) -> Monke {
-    let items = input[1][18..]
-        .split(", ")
-        .map(|str| str.parse::<usize>().unwrap())
-        .collect();
-    let inspect_operator = match input[2].chars().nth(23).unwrap() {
-        '*' => Operator::Multiply,
-        '+' => Operator::Add,
-        _ => panic!(),
-    };
-    let inspect_operator_value: OperatorValue = match &input[2][25..] {
-        "old" => OperatorValue::OwnSelf,
-        x => OperatorValue::Usize(String::from(x).parse::<usize>().unwrap()),
-    };
-    let inspect_operation = (inspect_operator, inspect_operator_value);
-    let test_div = String::from(&input[3][21..]).parse::<usize>().unwrap();
-    let test_true_i = String::from(&input[4][29..]).parse::<usize>().unwrap();
-    let test_false_i = String::from(&input[5][30..]).parse::<usize>().unwrap();
-    let test_addr = (test_true_i, test_false_i);
-
-    Monke {
-        items,
-        inspect_operation,
-        test_div,
-        test_addr,
-        inspect_count: 0,
+    fn divisible(&self, value: &u64, divisor: u64) -> bool {
+        (*value).is_multiple_of(divisor)
     }
 }
 
-fn run_round(monkes: &mut Vec<Monke>, worry_level_div: bool) {
-    let monke_product: usize = monkes.iter().map(|m| m.test_div).product();
-
-    for monke_i in 0..monkes.len() {
-        monkes[monke_i].inspect_count += monkes[monke_i].items.len();
-        for item_i in 0..monkes[monke_i].items.len() {
-            let operation_ingredient = match monkes[monke_i].inspect_operation.1 {
-                OperatorValue::OwnSelf => monkes[monke_i].items[item_i].clone(),
-                OperatorValue::Usize(x) => x,
-            };
-
-            let new_value = match monkes[monke_i].inspect_operation.0 {
-                Operator::Add => {
-                    if worry_level_div {
-                        ((monkes[monke_i].items[item_i].clone()) + (operation_ingredient)) / 3
-                    } else {
-                        (monkes[monke_i].items[item_i].clone() % monke_product)
-                            + (operation_ingredient)
-                    }
-                }
-                Operator::Multiply => {
-                    if worry_level_div {
-                        (monkes[monke_i].items[item_i].clone() * (operation_ingredient)) / 3
-                    } else {
-                        (monkes[monke_i].items[item_i].clone() % monke_product)
-                            * (operation_ingredient)
-                    }
+// Exact worry levels, for rules that divide. Division rounds down.
+struct Exact;
+
+impl Arithmetic for Exact {
+    type Value = BigInt;
+
+    fn number(&self, n: u64) -> BigInt {
+        BigInt::from(n)
+    }
+
+    fn apply(&self, operator: Operator, left: BigInt, right: BigInt) -> BigInt {
+        match operator {
+            Operator::Add => left + right,
+            Operator::Subtract => left - right,
+            Operator::Multiply => left * right,
+            Operator::Divide => {
+                if right.is_zero() {
+                    panic!("Division by zero in worry level {}", left);
                 }
-            };
-
-            if new_value.clone() % monkes[monke_i].test_div == 0 {
-                let i = monkes[monke_i].test_addr.0;
-                monkes[i].items.push(new_value);
-            } else {
-                let i = monkes[monke_i].test_addr.1;
-                monkes[i].items.push(new_value);
+                left.div_floor(&right)
             }
         }
-        monkes[monke_i].items = vec![];
     }
-}
-
-fn main() {
-    let input = fs::read_to_string("./input.txt").expect("File not loaded");
-    let mut monkes_1 = monke_lines(input.clone());
-    let mut monkes_2 = monke_lines(input);
 
-    for _ in 0..20 {
-        run_round(&mut monkes_1, true);
+    fn divisible(&self, value: &BigInt, divisor: u64) -> bool {
+        value.mod_floor(&BigInt::from(divisor)).is_zero()
     }
+}
 
-    for _ in 0..10000 {
-        run_round(&mut monkes_2, false);
+// Number of items each monkey inspected. After every inspection the `relief`
+// rule turns the new worry level into the one the item gets tested with.
+fn simulate<A: Arithmetic>(
+    monkeys: &[Monkey],
+    rounds: usize,
+    relief: &Expr,
+    arithmetic: &A,
+) -> Vec<usize> {
+    let number = |n| arithmetic.number(n);
+    let apply = |operator, left, right| arithmetic.apply(operator, left, right);
+
+    let mut items = monkeys
+        .iter()
+        .map(|m| m.items.iter().map(|i| arithmetic.number(*i)).collect())
+        .collect::<Vec<Vec<A::Value>>>();
+    let mut inspections = vec![0; monkeys.len()];
+
+    for _ in 0..rounds {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            inspections[i] += items[i].len();
+            for old in std::mem::take(&mut items[i]) {
+                let new = monkey.operation.eval(&old, &number, &apply);
+                let new = relief.eval(&new, &number, &apply);
+                let target = if arithmetic.divisible(&new, monkey.divisor) {
+                    monkey.targets.0
+                } else {
+                    monkey.targets.1
+                };
+                items[target].push(new);
+            }
+        }
     }
 
-    monkes_1.sort_by(|a, b| b.inspect_count.cmp(&a.inspect_count));
-    monkes_2.sort_by(|a, b| b.inspect_count.cmp(&a.inspect_count));
+    inspections
+}
+
+fn monkey_business(monkeys: &[Monkey], rounds: usize, relief: &Expr) -> usize {
+    let modular = !relief.has_division() && monkeys.iter().all(|m| !m.operation.has_division());
+    let mut inspections = if modular {
+        let modulus = monkeys.iter().fold(1, |lcm, m| lcm.lcm(&m.divisor));
+        simulate(monkeys, rounds, relief, &Modular(modulus))
+    } else {
+        simulate(monkeys, rounds, relief, &Exact)
+    };
+
+    inspections.sort_by(|a, b| b.cmp(a));
+    inspections.iter().take(2).product()
+}
 
-    let result_1 = monkes_1[0].inspect_count * monkes_1[1].inspect_count;
-    let result_2 = monkes_2[0].inspect_count * monkes_2[1].inspect_count;
+fn main() {
+    let input = fs::read_to_string("./input.txt").expect("File not loaded");
+    let monkeys = parse_notes(&input);
+
+    // `--rounds N` and `--relief EXPR` run a single custom game instead.
+    let rounds = parsed_arg::<usize>("--rounds");
+    let relief = arg_value("--relief").map(|r| parse_expr(&r));
+    if rounds.is_some() || relief.is_some() {
+        let relief = relief.unwrap_or(Expr::Old);
+        println!(
+            "{}",
+            monkey_business(&monkeys, rounds.unwrap_or(20), &relief)
+        );
+        return;
+    }
 
-    println!("{}", result_1);
-    println!("{}", result_2);
+    println!("{}", monkey_business(&monkeys, 20, &parse_expr("old / 3")));
+    println!("{}", monkey_business(&monkeys, 10000, &Expr::Old));
 }
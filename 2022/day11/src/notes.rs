@@ -0,0 +1,183 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, multispace0, one_of, space0, u64 as number},
+    combinator::{all_consuming, map, value},
+    multi::{many0, many1, separated_list0},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    IResult,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Old,
+    Number(u64),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+}
+
+impl Expr {
+    pub fn has_division(&self) -> bool {
+        match self {
+            Expr::Binary(left, operator, right) => {
+                *operator == Operator::Divide || left.has_division() || right.has_division()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn eval<T: Clone>(
+        &self,
+        old: &T,
+        number: &impl Fn(u64) -> T,
+        apply: &impl Fn(Operator, T, T) -> T,
+    ) -> T {
+        match self {
+            Expr::Old => old.clone(),
+            Expr::Number(n) => number(*n),
+            Expr::Binary(left, operator, right) => apply(
+                *operator,
+                left.eval(old, number, apply),
+                right.eval(old, number, apply),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Monkey {
+    pub items: Vec<u64>,
+    pub operation: Expr,
+    pub divisor: u64,
+    pub targets: (usize, usize),
+}
+
+// Whitespace, newlines included, is allowed around every token.
+fn token<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    delimited(multispace0, parser, space0)
+}
+
+fn binary(left: Expr, rest: Vec<(Operator, Expr)>) -> Expr {
+    rest.into_iter().fold(left, |left, (operator, right)| {
+        Expr::Binary(Box::new(left), operator, Box::new(right))
+    })
+}
+
+fn operator<'a>(symbols: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, Operator> {
+    map(token(one_of(symbols)), |c| match c {
+        '+' => Operator::Add,
+        '-' => Operator::Subtract,
+        '*' => Operator::Multiply,
+        _ => Operator::Divide,
+    })
+}
+
+fn factor(input: &str) -> IResult<&str, Expr> {
+    alt((
+        value(Expr::Old, token(tag("old"))),
+        map(token(number), Expr::Number),
+        delimited(token(char('(')), expr, token(char(')'))),
+    ))(input)
+}
+
+fn term(input: &str) -> IResult<&str, Expr> {
+    let (input, left) = factor(input)?;
+    let (input, rest) = many0(pair(operator("*/"), factor))(input)?;
+    Ok((input, binary(left, rest)))
+}
+
+// Usual precedence: `*` and `/` bind tighter than `+` and `-`, all of them
+// left associative.
+pub fn expr(input: &str) -> IResult<&str, Expr> {
+    let (input, left) = term(input)?;
+    let (input, rest) = many0(pair(operator("+-"), term))(input)?;
+    Ok((input, binary(left, rest)))
+}
+
+fn monkey(input: &str) -> IResult<&str, (u64, Monkey)> {
+    let (input, id) = delimited(token(tag("Monkey")), token(number), token(char(':')))(input)?;
+    let (input, items) = preceded(
+        pair(token(tag("Starting")), token(tag("items:"))),
+        separated_list0(token(char(',')), token(number)),
+    )(input)?;
+    let (input, operation) = preceded(
+        tuple((
+            token(tag("Operation:")),
+            token(tag("new")),
+            token(char('=')),
+        )),
+        expr,
+    )(input)?;
+    let (input, divisor) = preceded(
+        tuple((
+            token(tag("Test:")),
+            token(tag("divisible")),
+            token(tag("by")),
+        )),
+        token(number),
+    )(input)?;
+    let target = |condition: &'static str| {
+        preceded(
+            tuple((
+                token(tag("If")),
+                token(tag(condition)),
+                token(tag("throw")),
+                token(tag("to")),
+                token(tag("monkey")),
+            )),
+            token(number),
+        )
+    };
+    let (input, if_true) = target("true:")(input)?;
+    let (input, if_false) = target("false:")(input)?;
+
+    Ok((
+        input,
+        (
+            id,
+            Monkey {
+                items,
+                operation,
+                divisor,
+                targets: (if_true as usize, if_false as usize),
+            },
+        ),
+    ))
+}
+
+pub fn parse_notes(input: &str) -> Vec<Monkey> {
+    let (_, monkeys) = all_consuming(terminated(many1(monkey), multispace0))(input)
+        .unwrap_or_else(|e| panic!("Unable to parse monkey notes: {}", e));
+
+    for (i, (id, monkey)) in monkeys.iter().enumerate() {
+        if *id != i as u64 {
+            panic!("Expected monkey {}, found monkey {}", i, id);
+        }
+        if monkey.divisor == 0 {
+            panic!("Monkey {} tests divisibility by 0", id);
+        }
+        for target in [monkey.targets.0, monkey.targets.1] {
+            if target >= monkeys.len() || target == i {
+                panic!("Monkey {} throws to invalid monkey {}", id, target);
+            }
+        }
+    }
+
+    monkeys.into_iter().map(|(_, monkey)| monkey).collect()
+}
+
+// An expression on its own, like a relief rule given on the command line.
+pub fn parse_expr(input: &str) -> Expr {
+    all_consuming(terminated(expr, multispace0))(input)
+        .unwrap_or_else(|e| panic!("Invalid expression {:?}: {}", input, e))
+        .1
+}
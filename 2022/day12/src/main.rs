@@ -1,19 +1,55 @@
 use std::collections::{HashMap, VecDeque};
-use std::fs;
+use std::{env, fs};
 
-fn shortest_path_for_start(
+use common::diff::{self, Differential};
+use common::rng::Rng;
+
+struct Heightmap {
     terrain: HashMap<(usize, usize), usize>,
     start: (usize, usize),
+    alternative_starts: Vec<(usize, usize)>,
+    end: (usize, usize),
+    board_size: (usize, usize),
+}
+
+// Routes found by walking down from the end: how far every square is from it,
+// and which neighbour to step onto next to get there.
+struct Routes {
+    distances: HashMap<(usize, usize), usize>,
+    next: HashMap<(usize, usize), (usize, usize)>,
+}
+
+impl Routes {
+    fn steps_from(&self, start: (usize, usize)) -> Option<usize> {
+        self.distances.get(&start).copied()
+    }
+
+    // Every square from `start` to the end, both included.
+    fn route_from(&self, start: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        self.steps_from(start)?;
+        let mut route = vec![start];
+        while let Some(next) = self.next.get(route.last().unwrap()) {
+            route.push(*next);
+        }
+        Some(route)
+    }
+}
+
+// Forward search from a single start, kept as the reference the reverse search
+// is checked against with `--diff`.
+fn shortest_path_for_start(
+    terrain: &HashMap<(usize, usize), usize>,
+    start: (usize, usize),
     end: (usize, usize),
     board_size: (usize, usize),
-) -> usize {
-    let mut nodes_cost: HashMap<(usize, usize), usize> = HashMap::from([(start.clone(), 0)]);
-    let mut to_visit: VecDeque<(usize, usize)> = VecDeque::from([start.clone()]);
+) -> Option<usize> {
+    let mut nodes_cost: HashMap<(usize, usize), usize> = HashMap::from([(start, 0)]);
+    let mut to_visit: VecDeque<(usize, usize)> = VecDeque::from([start]);
 
     while !to_visit.is_empty() {
         let visiting = to_visit.pop_back().unwrap();
         let height = terrain.get(&visiting).unwrap();
-        let val = nodes_cost.get(&visiting).unwrap().clone();
+        let val = *nodes_cost.get(&visiting).unwrap();
         let mut positions_to_check: Vec<(usize, usize)> = vec![];
 
         if visiting.0 > 0 {
@@ -30,7 +66,7 @@ fn shortest_path_for_start(
         }
         positions_to_check = positions_to_check
             .iter()
-            .filter(|pos| terrain.get(pos).unwrap().clone() <= height + 1)
+            .filter(|pos| *terrain.get(pos).unwrap() <= height + 1)
             .cloned()
             .collect::<Vec<(usize, usize)>>();
 
@@ -41,7 +77,7 @@ fn shortest_path_for_start(
 
             match current_cost {
                 Some(val) => {
-                    if new_cost < val.clone() {
+                    if new_cost < *val {
                         nodes_cost.entry(position).and_modify(|c| *c = new_cost);
                         to_visit.push_back(position);
                     }
@@ -54,15 +90,84 @@ fn shortest_path_for_start(
         }
     }
 
-    match nodes_cost.get(&end) {
-        Some(val) => val.clone(),
-        None => 999999
+    nodes_cost.get(&end).copied()
+}
+
+// Walks down from the end with the climbing rule reversed, so a single pass
+// gives the distance from every square to the end.
+fn distances_to_end(terrain: &HashMap<(usize, usize), usize>, end: (usize, usize)) -> Routes {
+    let mut distances = HashMap::from([(end, 0)]);
+    let mut next = HashMap::new();
+    let mut to_visit = VecDeque::from([end]);
+
+    while let Some((x, y)) = to_visit.pop_front() {
+        let height = terrain[&(x, y)];
+        let distance = distances[&(x, y)];
+        let neighbours = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for neighbour in neighbours {
+            match terrain.get(&neighbour) {
+                Some(h) if h + 1 >= height && !distances.contains_key(&neighbour) => {
+                    distances.insert(neighbour, distance + 1);
+                    next.insert(neighbour, (x, y));
+                    to_visit.push_back(neighbour);
+                }
+                _ => {}
+            }
+        }
     }
+
+    Routes { distances, next }
 }
 
-fn main() {
-    let input = fs::read_to_string("./input.txt").expect("File not loaded");
+// The lowest square that's closest to the end, if any of them can reach it.
+fn closest_lowest_square(
+    terrain: &HashMap<(usize, usize), usize>,
+    routes: &Routes,
+) -> Option<(usize, usize)> {
+    terrain
+        .iter()
+        .filter(|(_, height)| **height == 'a' as usize)
+        .filter_map(|(position, _)| routes.steps_from(*position).map(|s| (s, *position)))
+        .min()
+        .map(|(_, position)| position)
+}
 
+// The height map with the route drawn over it, pointing the way to `E`.
+fn draw_route(map: &Heightmap, route: &[(usize, usize)]) -> String {
+    let mut rows = (0..=map.board_size.1)
+        .map(|y| {
+            (0..=map.board_size.0)
+                // Ragged rows leave cells with no terrain; those stay blank.
+                .map(|x| {
+                    map.terrain
+                        .get(&(x, y))
+                        .map_or(' ', |height| (*height as u8) as char)
+                })
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+    for step in route.windows(2) {
+        let ((x, y), to) = (step[0], step[1]);
+        rows[y][x] = match to {
+            _ if to.0 > x => '>',
+            _ if to.0 < x => '<',
+            _ if to.1 > y => 'v',
+            _ => '^',
+        };
+    }
+    rows[map.end.1][map.end.0] = 'E';
+    rows.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn parse(input: &str) -> Heightmap {
     let mut terrain: HashMap<(usize, usize), usize> = HashMap::new();
     let mut start: (usize, usize) = (0, 0);
     let mut alternative_starts: Vec<(usize, usize)> = vec![];
@@ -97,18 +202,126 @@ fn main() {
         }
     }
 
-    let shortest_path_first = shortest_path_for_start(terrain.clone(), start, end, board_size);
-    // This is super slow, but servicable for the amount of data in the set.
-    //
-    // The alternative: Reverse the BFS without looking for the end and then iterate
-    //                  through all positions in `terrain` that have the lowest
-    //                  height. Caveat: make sure the movements is legal.
-    let min_path = alternative_starts
-        .iter()
-        .map(|s| shortest_path_for_start(terrain.clone(), s.clone(), end, board_size))
-        .min()
-        .unwrap();
+    Heightmap {
+        terrain,
+        start,
+        alternative_starts,
+        end,
+        board_size,
+    }
+}
+
+// Ramps up to the far corner with random dips, so some squares are stuck
+// behind walls while others can still reach the end. Climbing from `a` to `z`
+// takes at least 25 steps, which is what keeps these from being any smaller.
+fn small_heightmap(rng: &mut Rng) -> Vec<String> {
+    let (width, height) = (rng.range_usize(15, 21), rng.range_usize(12, 16));
+    let span = width + height - 2;
+    let mut rows = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let level = ((x + y) * 25 / span).saturating_sub(rng.range_usize(0, 3));
+                    (b'a' + level as u8) as char
+                })
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+    let (s_x, s_y) = loop {
+        let (x, y) = (rng.range_usize(0, width), rng.range_usize(0, height));
+        if (x, y) != (width - 1, height - 1) {
+            break (x, y);
+        }
+    };
+    rows[s_y][s_x] = 'S';
+    rows[height - 1][width - 1] = 'E';
+    rows.iter().map(|r| r.iter().collect()).collect()
+}
+
+fn shrink_heightmap(rows: &[String]) -> Vec<Vec<String>> {
+    let columns = (0..rows[0].len())
+        .map(|x| rows.iter().map(|r| r.as_bytes()[x]).collect::<Vec<u8>>())
+        .collect::<Vec<Vec<u8>>>();
+    let without_columns = diff::shrink_vec(&columns).into_iter().map(|columns| {
+        (0..rows.len())
+            .map(|y| columns.iter().map(|c| c[y] as char).collect::<String>())
+            .collect::<Vec<String>>()
+    });
+    let flattened = diff::shrink_each(rows, |row| {
+        (0..row.len())
+            .filter(|x| !matches!(row.as_bytes()[*x], b'a' | b'S' | b'E'))
+            .map(|x| {
+                let mut row = row.clone();
+                row.replace_range(x..x + 1, "a");
+                row
+            })
+            .collect()
+    });
+
+    diff::shrink_vec(rows)
+        .into_iter()
+        .chain(without_columns)
+        .chain(flattened)
+        .filter(|rows| {
+            let cells = rows.concat();
+            !rows.is_empty() && cells.contains('S') && cells.contains('E')
+        })
+        .collect()
+}
+
+fn differential() -> Differential<Vec<String>, Option<usize>> {
+    Differential::new(
+        "2022/12 fewest steps from any lowest square",
+        small_heightmap,
+        |rows: &Vec<String>| {
+            let map = parse(&rows.join("\n"));
+            map.alternative_starts
+                .iter()
+                .chain([&map.start])
+                .filter_map(|s| shortest_path_for_start(&map.terrain, *s, map.end, map.board_size))
+                .min()
+        },
+        |rows: &Vec<String>| {
+            let map = parse(&rows.join("\n"));
+            let routes = distances_to_end(&map.terrain, map.end);
+            closest_lowest_square(&map.terrain, &routes).and_then(|s| routes.steps_from(s))
+        },
+    )
+    .shrink(|rows| shrink_heightmap(rows))
+}
+
+fn main() {
+    if env::args().any(|a| a == "--diff") {
+        return differential().run();
+    }
+
+    let input = fs::read_to_string("./input.txt").expect("File not loaded");
+    let map = parse(&input);
+
+    // A single search down from the end answers both parts.
+    let routes = distances_to_end(&map.terrain, map.end);
+    let first_route = routes.route_from(map.start);
+    let best_route =
+        closest_lowest_square(&map.terrain, &routes).and_then(|s| routes.route_from(s));
 
-    println!("{}", shortest_path_first);
-    println!("{}", min_path);
+    for route in [&first_route, &best_route] {
+        match route {
+            Some(route) => println!("{}", route.len() - 1),
+            None => println!("No route to the end"),
+        }
+    }
+    for route in [first_route, best_route].into_iter().flatten() {
+        println!();
+        println!("{}", draw_route(&map, &route));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimised_matches_reference() {
+        differential().check(0, 100).unwrap();
+    }
 }
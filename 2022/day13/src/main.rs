@@ -1,130 +1,61 @@
-use std::cmp::Ordering;
-use std::fs;
+use std::{env, fs};
 
-#[derive(Clone, PartialEq)]
-enum Value {
-    Nested(Vec<Value>),
-    Number(usize),
-}
-
-fn parse_line(line: &str) -> Value {
-    let chars: Vec<_> = line.chars().collect();
-    parse_list(chars).0
-}
+use crate::packet::Packet;
 
-fn parse_list(chars: Vec<char>) -> (Value, usize) {
-    let mut i = 1;
-    let mut list_content: Vec<Value> = vec![];
-    let mut number_acc: Vec<char> = vec![];
-
-    while i < chars.len() {
-        match chars[i] {
-            ']' => {
-                if !number_acc.is_empty() {
-                    list_content.push(Value::Number(
-                        String::from_iter(number_acc).parse().unwrap(),
-                    ));
-                }
-                break;
-            }
-            ',' => {
-                if !number_acc.is_empty() {
-                    list_content.push(Value::Number(
-                        String::from_iter(number_acc).parse().unwrap(),
-                    ));
-                    number_acc = vec![];
-                }
-            }
-            '[' => {
-                let (list, characters_to_skip) =
-                    parse_list(chars[i..].iter().map(|e| e.clone()).collect());
-                i += characters_to_skip;
-                list_content.push(list);
-            }
-            x => {
-                number_acc.push(x);
-            }
-        };
-        i += 1;
-    }
+mod packet;
 
-    (Value::Nested(list_content), i)
-}
-
-fn packet_order(left: Value, right: Value) -> Ordering {
-    match (left.clone(), right.clone()) {
-        (Value::Number(x), Value::Number(y)) => x.cmp(&y),
-        (Value::Number(x), Value::Nested(y)) => {
-            packet_order(Value::Nested(vec![Value::Number(x)]), Value::Nested(y))
-        }
-        (Value::Nested(x), Value::Number(y)) => {
-            packet_order(Value::Nested(x), Value::Nested(vec![Value::Number(y)]))
-        }
-        (Value::Nested(x), Value::Nested(y)) => {
-            if x.len() == 0 && y.len() == 0 {
-                Ordering::Equal
-            } else if x.len() == 0 {
-                Ordering::Less
-            } else if x.len() > 0 && y.len() == 0 {
-                Ordering::Greater
-            } else {
-                match packet_order(x[0].clone(), y[0].clone()) {
-                    Ordering::Equal => {
-                        if x.len() > 1 && y.len() == 1 {
-                            Ordering::Greater
-                        } else if x.len() == 1 && y.len() > 1 {
-                            Ordering::Less
-                        } else {
-                            packet_order(
-                                Value::Nested(x[1..].to_vec()),
-                                Value::Nested(y[1..].to_vec()),
-                            )
-                        }
-                    }
-                    order => order,
-                }
-            }
-        }
-    }
+fn parse_line(line: &str) -> Packet {
+    line.parse()
+        .unwrap_or_else(|e| panic!("Invalid packet {:?}: {}", line, e))
 }
 
 fn main() {
     let input = fs::read_to_string("./input.txt").expect("File not loaded");
-    let mut pairs: Vec<(Value, Value)> = vec![];
-
-    input.split("\n\n").for_each(|lines_pair| {
-        let lines: Vec<&str> = lines_pair.lines().collect();
-        let left = lines[0];
-        let right = lines[1];
-        pairs.push((parse_line(left), parse_line(right)));
-    });
+    let pairs: Vec<(Packet, Packet)> = input
+        .split("\n\n")
+        .filter(|lines_pair| !lines_pair.trim().is_empty())
+        .map(|lines_pair| {
+            let lines: Vec<&str> = lines_pair.lines().collect();
+            (parse_line(lines[0]), parse_line(lines[1]))
+        })
+        .collect();
 
     let result_1 = pairs
         .iter()
         .enumerate()
-        .map(|(i, (left, right))| {
-            if packet_order(left.clone(), right.clone()) == Ordering::Less {
-                i + 1
-            } else {
-                0
-            }
-        })
+        .filter(|(_, (left, right))| left < right)
+        .map(|(i, _)| i + 1)
         .sum::<usize>();
 
-    let mut flat_packets: Vec<Value> = pairs.iter().fold(vec![], |mut acc, el| {
-        acc.push(el.0.clone());
-        acc.push(el.1.clone());
-        acc
-    });
-    // Add "dividers"
-    let divider_1 = Value::Nested(vec![Value::Nested(vec![Value::Number(2)])]);
-    let divider_2 = Value::Nested(vec![Value::Nested(vec![Value::Number(6)])]);
-    flat_packets.push(divider_1.clone());
-    flat_packets.push(divider_2.clone());
-    flat_packets.sort_by(|a, b| packet_order(a.clone(), b.clone()));
-
-    let result_2 = (flat_packets.iter().position(|e| e.clone() == divider_1).unwrap() + 1) * (flat_packets.iter().position(|e| e.clone() == divider_2).unwrap() + 1);
+    let dividers = [Packet::divider(2), Packet::divider(6)];
+    let mut packets: Vec<Packet> = pairs
+        .into_iter()
+        .flat_map(|(left, right)| [left, right])
+        .chain(dividers.clone())
+        .collect();
+    packets.sort();
+
+    // Packets like `[2]` compare equal to a divider. The sort is stable and the
+    // dividers went in last, so each one sits after every packet equal to it.
+    let result_2 = dividers
+        .iter()
+        .map(|divider| packets.partition_point(|p| p <= divider))
+        .product::<usize>();
 
     println!("{}", result_1);
     println!("{}", result_2);
+
+    // `--json` also dumps the sorted packets, dividers included, one JSON
+    // array per line. Packets compare equal to lists of themselves, so the
+    // round trip is checked on the text instead.
+    if env::args().any(|a| a == "--json") {
+        for packet in &packets {
+            let json = packet.to_json();
+            debug_assert_eq!(
+                Packet::from_json(&json).unwrap().to_string(),
+                packet.to_string()
+            );
+            println!("{}", json);
+        }
+    }
 }
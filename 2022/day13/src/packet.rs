@@ -0,0 +1,187 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+#[derive(Clone, Debug)]
+pub enum Packet {
+    List(Vec<Packet>),
+    Number(u64),
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.position)
+    }
+}
+
+// Walks the line once, byte by byte, without copying any of it.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn packet(&mut self) -> Result<Packet, ParseError> {
+        match self.bytes.get(self.position) {
+            Some(b'[') => self.list(),
+            Some(b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("Expected `[` or a number")),
+            None => Err(self.error("Unexpected end of packet")),
+        }
+    }
+
+    fn list(&mut self) -> Result<Packet, ParseError> {
+        self.position += 1;
+        let mut items = vec![];
+        if self.bytes.get(self.position) == Some(&b']') {
+            self.position += 1;
+            return Ok(Packet::List(items));
+        }
+        loop {
+            items.push(self.packet()?);
+            match self.bytes.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Packet::List(items));
+                }
+                _ => return Err(self.error("Expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Packet, ParseError> {
+        let mut number: u64 = 0;
+        while let Some(digit @ b'0'..=b'9') = self.bytes.get(self.position) {
+            number = number
+                .checked_mul(10)
+                .and_then(|n| n.checked_add((digit - b'0') as u64))
+                .ok_or_else(|| self.error("Number too large"))?;
+            self.position += 1;
+        }
+        Ok(Packet::Number(number))
+    }
+}
+
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            bytes: line.trim().as_bytes(),
+            position: 0,
+        };
+        let packet = parser.packet()?;
+        if parser.position != parser.bytes.len() {
+            return Err(parser.error("Trailing characters after packet"));
+        }
+        Ok(packet)
+    }
+}
+
+// Written back the way the puzzle input has it, so parsing what's printed
+// gives the same packet.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Packet::Number(n) => write!(f, "{}", n),
+            Packet::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+// A number compared with a list is treated as a list holding just that
+// number, and lists compare item by item with the shorter one first.
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Number(x), Packet::Number(y)) => x.cmp(y),
+            (Packet::List(x), Packet::List(y)) => x.cmp(y),
+            (Packet::Number(_), Packet::List(y)) => std::slice::from_ref(self).cmp(y),
+            (Packet::List(x), Packet::Number(_)) => x[..].cmp(std::slice::from_ref(other)),
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Equality follows the ordering, so `2` and `[2]` are equal packets.
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet {}
+
+impl From<&Packet> for Value {
+    fn from(packet: &Packet) -> Self {
+        match packet {
+            Packet::Number(n) => Value::from(*n),
+            Packet::List(items) => Value::Array(items.iter().map(Value::from).collect()),
+        }
+    }
+}
+
+// Only arrays of non-negative integers are packets.
+impl TryFrom<&Value> for Packet {
+    type Error = String;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Array(items) => items
+                .iter()
+                .map(Packet::try_from)
+                .collect::<Result<Vec<Packet>, String>>()
+                .map(Packet::List),
+            Value::Number(n) => n
+                .as_u64()
+                .map(Packet::Number)
+                .ok_or_else(|| format!("{} isn't a packet number", n)),
+            other => Err(format!("{} isn't part of a packet", other)),
+        }
+    }
+}
+
+impl Packet {
+    pub fn divider(n: u64) -> Self {
+        Packet::List(vec![Packet::List(vec![Packet::Number(n)])])
+    }
+
+    pub fn to_json(&self) -> String {
+        Value::from(self).to_string()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value = serde_json::from_str::<Value>(json).map_err(|e| e.to_string())?;
+        Packet::try_from(&value)
+    }
+}
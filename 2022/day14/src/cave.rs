@@ -0,0 +1,211 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Floor {
+    // Sand that falls past the lowest rock is lost, and the first grain that
+    // does stops the simulation.
+    Abyss,
+    // An endless floor two rows below the lowest rock. Sand piles up until
+    // every source is buried.
+    Solid,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Air,
+    Rock,
+    Sand,
+}
+
+// The part of the cave sand can ever reach, stored row by row. A pile can't
+// spread further sideways than it falls, so the grid only needs to be as wide
+// as the floor is deep on each side of the sources.
+pub struct Cave {
+    cells: Vec<Cell>,
+    x_min: i32,
+    width: usize,
+    // Rows 0 up to the last one sand can move through; the floor (or the
+    // abyss) is just below it.
+    height: usize,
+    floor: Floor,
+    sources: Vec<(i32, i32)>,
+    // For every source, the positions the last grain from it fell through.
+    // The next grain takes the same way down, so it starts from the end.
+    paths: Vec<Vec<(usize, usize)>>,
+    buried: Vec<bool>,
+    resting: usize,
+}
+
+impl Cave {
+    pub fn new(rocks: &[(i32, i32)], sources: &[(i32, i32)], floor: Floor) -> Self {
+        if sources.is_empty() {
+            panic!("The cave needs at least one source of sand");
+        }
+        let lowest = rocks.iter().map(|r| r.1).max().unwrap_or(0);
+        let height = (lowest + 2) as usize;
+        for source in sources {
+            if source.1 < 0 || source.1 as usize >= height {
+                panic!("Source {:?} is outside the cave", source);
+            }
+        }
+
+        let reach = |s: &(i32, i32)| height as i32 - s.1;
+        let x_min = sources
+            .iter()
+            .map(|s| s.0 - reach(s))
+            .chain(rocks.iter().map(|r| r.0))
+            .min()
+            .unwrap();
+        let x_max = sources
+            .iter()
+            .map(|s| s.0 + reach(s))
+            .chain(rocks.iter().map(|r| r.0))
+            .max()
+            .unwrap();
+        let width = (x_max - x_min + 1) as usize;
+
+        let mut cave = Cave {
+            cells: vec![Cell::Air; width * height],
+            x_min,
+            width,
+            height,
+            floor,
+            sources: sources.to_vec(),
+            paths: vec![],
+            buried: vec![false; sources.len()],
+            resting: 0,
+        };
+        for rock in rocks {
+            if rock.1 >= 0 {
+                let index = cave.index(cave.column(rock.0), rock.1 as usize);
+                cave.cells[index] = Cell::Rock;
+            }
+        }
+        cave.paths = sources
+            .iter()
+            .map(|s| {
+                let start = (cave.column(s.0), s.1 as usize);
+                if cave.cells[cave.index(start.0, start.1)] != Cell::Air {
+                    panic!("Source {:?} is inside rock", s);
+                }
+                vec![start]
+            })
+            .collect();
+        cave
+    }
+
+    fn column(&self, x: i32) -> usize {
+        (x - self.x_min) as usize
+    }
+
+    fn index(&self, column: usize, y: usize) -> usize {
+        y * self.width + column
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Cell {
+        if y as usize == self.height && self.floor == Floor::Solid {
+            return Cell::Rock;
+        }
+        if x < self.x_min || y < 0 || y as usize >= self.height {
+            return Cell::Air;
+        }
+        let column = self.column(x);
+        if column >= self.width {
+            return Cell::Air;
+        }
+        self.cells[self.index(column, y as usize)]
+    }
+
+    // Drops one grain from the given source. It ends up where it rests, or
+    // None when it fell into the abyss or the source is already buried.
+    fn drop_grain(&mut self, source: usize) -> Option<(usize, usize)> {
+        let mut path = std::mem::take(&mut self.paths[source]);
+        // Sand from other sources may have landed on the way down since.
+        if self.sources.len() > 1 {
+            if let Some(blocked) = path
+                .iter()
+                .position(|(x, y)| self.cells[self.index(*x, *y)] != Cell::Air)
+            {
+                path.truncate(blocked);
+            }
+        }
+
+        let landed = loop {
+            let Some(&(x, y)) = path.last() else {
+                self.buried[source] = true;
+                break None;
+            };
+            if y + 1 == self.height {
+                if self.floor == Floor::Abyss {
+                    break None;
+                }
+                path.pop();
+                break Some((x, y));
+            }
+            let below = [x, x.wrapping_sub(1), x + 1]
+                .into_iter()
+                .find(|nx| *nx < self.width && self.cells[self.index(*nx, y + 1)] == Cell::Air);
+            match below {
+                Some(nx) => path.push((nx, y + 1)),
+                None => {
+                    path.pop();
+                    break Some((x, y));
+                }
+            }
+        };
+
+        if let Some((x, y)) = landed {
+            let index = self.index(x, y);
+            self.cells[index] = Cell::Sand;
+            self.resting += 1;
+            if path.is_empty() {
+                self.buried[source] = true;
+            }
+        }
+        self.paths[source] = path;
+        landed
+    }
+
+    // Runs until sand reaches the abyss or every source is buried, taking
+    // grains from each source in turn. `settled` sees the cave after every
+    // grain comes to rest.
+    pub fn fill(&mut self, mut settled: impl FnMut(&Cave)) -> usize {
+        'filling: loop {
+            let mut dropped = false;
+            for source in 0..self.sources.len() {
+                if self.buried[source] {
+                    continue;
+                }
+                match self.drop_grain(source) {
+                    Some(_) => {
+                        dropped = true;
+                        settled(self);
+                    }
+                    None if self.floor == Floor::Abyss && !self.buried[source] => {
+                        break 'filling;
+                    }
+                    None => {}
+                }
+            }
+            if !dropped {
+                break;
+            }
+        }
+        self.resting
+    }
+
+    // Rock, resting sand and the sources, with the floor when there is one.
+    pub fn to_grid(&self) -> Vec<Vec<char>> {
+        let rows = self.height as i32 + (self.floor == Floor::Solid) as i32;
+        (0..rows)
+            .map(|y| {
+                (self.x_min..self.x_min + self.width as i32)
+                    .map(|x| match self.get(x, y) {
+                        Cell::Rock => '#',
+                        Cell::Sand => 'o',
+                        Cell::Air if self.sources.contains(&(x, y)) => '+',
+                        Cell::Air => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
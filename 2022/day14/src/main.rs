@@ -1,52 +1,34 @@
-use std::cmp::max;
-use std::collections::HashSet;
-use std::fs;
+use std::cmp::{max, min};
+use std::{env, fs};
 
-fn create_board(input: String) -> (HashSet<(i32, i32)>, (i32, i32)) {
-    let mut stones_map: HashSet<(i32, i32)> = HashSet::new();
-    let mut board_size: (i32, i32) = (500, 0);
+use common::render::{Frames, Palette};
+
+use crate::cave::{Cave, Floor};
+
+mod cave;
+
+// Every square of rock the paths in the scan go through.
+fn parse_rocks(input: &str) -> Vec<(i32, i32)> {
+    let mut rocks: Vec<(i32, i32)> = vec![];
 
     for line in input.lines() {
         for window in line
             .split(" -> ")
-            .map(|s| String::from(s))
+            .map(String::from)
             .collect::<Vec<String>>()
             .windows(2)
         {
             let from = input_str_to_coords(window[0].clone());
             let to = input_str_to_coords(window[1].clone());
 
-            let x_max = max(from.0, to.0);
-            let y_max = max(from.1, to.1);
-            if x_max > board_size.0 {
-                board_size.0 = x_max;
-            }
-            if y_max > board_size.1 {
-                board_size.1 = y_max;
-            }
-
-            if from.0 == to.0 {
-                let range = if from.1 > to.1 {
-                    to.1..=from.1
-                } else {
-                    from.1..=to.1
-                };
-                for y in range {
-                    stones_map.insert((from.0, y));
-                }
-            } else {
-                let range = if from.0 > to.0 {
-                    to.0..=from.0
-                } else {
-                    from.0..=to.0
-                };
-                for x in range {
-                    stones_map.insert((x, from.1));
+            for x in min(from.0, to.0)..=max(from.0, to.0) {
+                for y in min(from.1, to.1)..=max(from.1, to.1) {
+                    rocks.push((x, y));
                 }
             }
         }
     }
-    (stones_map, board_size)
+    rocks
 }
 
 fn input_str_to_coords(input: String) -> (i32, i32) {
@@ -59,75 +41,34 @@ fn input_str_to_coords(input: String) -> (i32, i32) {
 
 fn main() {
     let input = fs::read_to_string("./input.txt").expect("File not loaded");
-    let (mut stones_map, board_size) = create_board(input);
-    let sand_start: (i32, i32) = (500, 0);
-    let mut sand_pieces_counter = 0;
-    let mut new_sand_piece = sand_start.clone();
-
-    loop {
-        if new_sand_piece.1 > board_size.1 {
-            break;
-        }
-
-        match stones_map.get(&(new_sand_piece.0, new_sand_piece.1 + 1)) {
-            Some(_) => match stones_map.get(&(new_sand_piece.0 - 1, new_sand_piece.1 + 1)) {
-                Some(_) => match stones_map.get(&(new_sand_piece.0 + 1, new_sand_piece.1 + 1)) {
-                    Some(_) => {
-                        stones_map.insert((new_sand_piece.0, new_sand_piece.1));
-                        new_sand_piece = sand_start.clone();
-                        sand_pieces_counter += 1;
-                    }
-                    None => {
-                        new_sand_piece.0 += 1;
-                        new_sand_piece.1 += 1;
-                    }
-                },
-                None => {
-                    new_sand_piece.0 -= 1;
-                    new_sand_piece.1 += 1;
-                }
-            },
-            None => {
-                new_sand_piece.1 += 1;
-            }
-        };
-    }
-
-    println!("{}", sand_pieces_counter);
+    let rocks = parse_rocks(&input);
 
-    for x in (-board_size.0 * 10)..(board_size.0 * 10) {
-        stones_map.insert((x, board_size.1 + 2));
+    // Sand pours from 500,0 unless `--source X,Y` says otherwise. Give it more
+    // than once for several sources.
+    let args = env::args().collect::<Vec<String>>();
+    let mut sources = args
+        .windows(2)
+        .filter(|pair| pair[0] == "--source")
+        .map(|pair| input_str_to_coords(pair[1].clone()))
+        .collect::<Vec<(i32, i32)>>();
+    if sources.is_empty() {
+        sources.push((500, 0));
     }
 
-   new_sand_piece = sand_start.clone();
+    let mut frames = Frames::from_args(
+        Palette::new([20, 20, 30])
+            .with('#', [110, 100, 90])
+            .with('o', [230, 190, 90])
+            .with('+', [220, 60, 60]),
+    );
 
-    loop {
-        match stones_map.get(&(new_sand_piece.0, new_sand_piece.1 + 1)) {
-            Some(_) => match stones_map.get(&(new_sand_piece.0 - 1, new_sand_piece.1 + 1)) {
-                Some(_) => match stones_map.get(&(new_sand_piece.0 + 1, new_sand_piece.1 + 1)) {
-                    Some(_) => {
-                        sand_pieces_counter += 1;
-                        if new_sand_piece == sand_start {
-                            break;
-                        }
-                        stones_map.insert((new_sand_piece.0, new_sand_piece.1));
-                        new_sand_piece = sand_start.clone();
-                    }
-                    None => {
-                        new_sand_piece.0 += 1;
-                        new_sand_piece.1 += 1;
-                    }
-                },
-                None => {
-                    new_sand_piece.0 -= 1;
-                    new_sand_piece.1 += 1;
-                }
-            },
-            None => {
-                new_sand_piece.1 += 1;
+    for floor in [Floor::Abyss, Floor::Solid] {
+        let mut cave = Cave::new(&rocks, &sources, floor);
+        let resting = cave.fill(|cave| {
+            if let Some(frames) = frames.as_mut() {
+                frames.push(&cave.to_grid());
             }
-        };
+        });
+        println!("{}", resting);
     }
-
-    println!("{}", sand_pieces_counter);
 }
@@ -0,0 +1,181 @@
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+
+pub type Coords = (i64, i64);
+
+#[derive(Clone, Debug)]
+pub struct Sensor {
+    pub position: Coords,
+    pub beacon: Coords,
+    pub radius: i64,
+}
+
+impl Sensor {
+    pub fn new(position: Coords, beacon: Coords) -> Self {
+        Sensor {
+            position,
+            beacon,
+            radius: distance(position, beacon),
+        }
+    }
+
+    pub fn covers(&self, point: Coords) -> bool {
+        distance(self.position, point) <= self.radius
+    }
+
+    // The cells of row `y` within range, if it gets that far.
+    fn row(&self, y: i64) -> Option<RangeInclusive<i64>> {
+        let remaining = self.radius - (self.position.1 - y).abs();
+        (remaining >= 0).then(|| self.position.0 - remaining..=self.position.0 + remaining)
+    }
+}
+
+fn distance(a: Coords, b: Coords) -> i64 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
+
+// Sorts and merges ranges that overlap or touch.
+fn merged(mut ranges: Vec<RangeInclusive<i64>>) -> Vec<RangeInclusive<i64>> {
+    ranges.sort_by_key(|r| *r.start());
+    let mut result: Vec<RangeInclusive<i64>> = vec![];
+    for range in ranges {
+        match result.last_mut() {
+            Some(last) if *range.start() <= last.end() + 1 => {
+                if range.end() > last.end() {
+                    *last = *last.start()..=*range.end();
+                }
+            }
+            _ => result.push(range),
+        }
+    }
+    result
+}
+
+fn clipped(range: &RangeInclusive<i64>, to: &RangeInclusive<i64>) -> Option<RangeInclusive<i64>> {
+    let (start, end) = (*range.start().max(to.start()), *range.end().min(to.end()));
+    (start <= end).then_some(start..=end)
+}
+
+fn len(range: &RangeInclusive<i64>) -> usize {
+    (range.end() - range.start() + 1) as usize
+}
+
+// Every question about what the sensors can see, answered from the sensors
+// alone.
+pub struct Coverage {
+    pub sensors: Vec<Sensor>,
+    // Each beacon once, even when several sensors report it.
+    pub beacons: BTreeSet<Coords>,
+}
+
+impl Coverage {
+    pub fn new(sensors: Vec<Sensor>) -> Self {
+        let beacons = sensors.iter().map(|s| s.beacon).collect();
+        Coverage { sensors, beacons }
+    }
+
+    pub fn covers(&self, point: Coords) -> bool {
+        self.sensors.iter().any(|s| s.covers(point))
+    }
+
+    // The covered cells of row `y`, as sorted, disjoint ranges.
+    pub fn row(&self, y: i64) -> Vec<RangeInclusive<i64>> {
+        merged(self.sensors.iter().filter_map(|s| s.row(y)).collect())
+    }
+
+    // Number of covered cells in the region, beacons included.
+    pub fn covered_in(&self, xs: &RangeInclusive<i64>, ys: &RangeInclusive<i64>) -> usize {
+        ys.clone()
+            .map(|y| {
+                self.row(y)
+                    .iter()
+                    .filter_map(|r| clipped(r, xs))
+                    .map(|r| len(&r))
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    // Cells of row `y` that can't hold a beacon: covered, and not one of the
+    // beacons already found.
+    pub fn no_beacon_in_row(&self, y: i64) -> usize {
+        let covered = self.row(y).iter().map(len).sum::<usize>();
+        covered - self.beacons.iter().filter(|b| b.1 == y).count()
+    }
+
+    // Every cell of the region no sensor sees, found row by row from the gaps
+    // between covered ranges.
+    pub fn uncovered(&self, xs: &RangeInclusive<i64>, ys: &RangeInclusive<i64>) -> Vec<Coords> {
+        let mut cells = vec![];
+        for y in ys.clone() {
+            let mut x = *xs.start();
+            for range in self.row(y) {
+                if *range.start() > x {
+                    cells.extend((x..*range.start().min(&(xs.end() + 1))).map(|x| (x, y)));
+                }
+                x = x.max(range.end() + 1);
+            }
+            cells.extend((x..=*xs.end()).map(|x| (x, y)));
+        }
+        cells
+    }
+
+    // Uncovered cells of the region boxed in on every side by sensor ranges
+    // or the region's edges, like a lone beacon would be. Turned 45 degrees,
+    // with u = x + y and v = x - y, each sensor covers a square, so such a
+    // cell sits where a line just past one square's side in u meets one just
+    // past a side in v. Only those crossings need checking, however big the
+    // region is.
+    pub fn gaps(&self, xs: &RangeInclusive<i64>, ys: &RangeInclusive<i64>) -> Vec<Coords> {
+        let corners = [
+            (*xs.start(), *ys.start()),
+            (*xs.start(), *ys.end()),
+            (*xs.end(), *ys.start()),
+            (*xs.end(), *ys.end()),
+        ];
+        let mut us = BTreeSet::new();
+        let mut vs = BTreeSet::new();
+        for sensor in &self.sensors {
+            let (u, v) = (
+                sensor.position.0 + sensor.position.1,
+                sensor.position.0 - sensor.position.1,
+            );
+            us.extend([u - sensor.radius - 1, u + sensor.radius + 1]);
+            vs.extend([v - sensor.radius - 1, v + sensor.radius + 1]);
+        }
+        for (x, y) in corners {
+            us.insert(x + y);
+            vs.insert(x - y);
+        }
+
+        let mut candidates = BTreeSet::from(corners);
+        for u in &us {
+            for v in &vs {
+                if (u - v) % 2 == 0 {
+                    candidates.insert(((u + v) / 2, (u - v) / 2));
+                }
+            }
+            // Where the line meets the region's edges.
+            for x in [*xs.start(), *xs.end()] {
+                candidates.insert((x, u - x));
+            }
+            for y in [*ys.start(), *ys.end()] {
+                candidates.insert((u - y, y));
+            }
+        }
+        for v in &vs {
+            for x in [*xs.start(), *xs.end()] {
+                candidates.insert((x, x - v));
+            }
+            for y in [*ys.start(), *ys.end()] {
+                candidates.insert((v + y, y));
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|(x, y)| xs.contains(x) && ys.contains(y))
+            .filter(|point| !self.covers(*point))
+            .collect()
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+// Valves in the order they're opened, each with the minutes left once it is.
+type Schedule = Vec<(u32, usize)>;
+
+// Valves by index, in the order the scan lists them.
+pub struct Valves {
+    pub names: Vec<String>,
+    pub flows: Vec<u32>,
+    // Minutes to walk from one valve to another.
+    pub distances: Vec<Vec<u32>>,
+}
+
+// What a group of agents should do: the total pressure released, and what
+// each agent opens.
+pub struct Plan {
+    pub pressure: u32,
+    pub schedules: Vec<Schedule>,
+}
+
+impl Valves {
+    pub fn new(names: Vec<String>, flows: Vec<u32>, tunnels: &[Vec<usize>]) -> Self {
+        Valves {
+            distances: floyd_warshall(tunnels),
+            names,
+            flows,
+        }
+    }
+
+    pub fn id(&self, name: &str) -> usize {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .unwrap_or_else(|| panic!("No valve {}", name))
+    }
+
+    // Best pressure for every set of valves one agent can open in time. Sets
+    // are bitmasks over `useful`; sets nobody can open in time stay at 0.
+    fn best_by_set(&self, start: usize, time: u32, useful: &[usize]) -> Vec<u32> {
+        let mut best = vec![0; 1 << useful.len()];
+        let mut seen = HashMap::new();
+        self.explore(start, time, 0, 0, useful, &mut seen, &mut best);
+        best
+    }
+
+    // Walks the orders of opening valves there's time for. Reaching the same
+    // valve with the same valves open and the same time left, but less
+    // pressure released than before, can't lead anywhere better.
+    #[allow(clippy::too_many_arguments)]
+    fn explore(
+        &self,
+        valve: usize,
+        remaining: u32,
+        opened: usize,
+        pressure: u32,
+        useful: &[usize],
+        seen: &mut HashMap<(usize, usize, u32), u32>,
+        best: &mut [u32],
+    ) {
+        match seen.get(&(valve, opened, remaining)) {
+            Some(before) if *before >= pressure => return,
+            _ => seen.insert((valve, opened, remaining), pressure),
+        };
+        best[opened] = best[opened].max(pressure);
+        for (bit, next) in useful.iter().enumerate() {
+            if opened & (1 << bit) != 0 {
+                continue;
+            }
+            // Walking there, then a minute to open it.
+            let cost = self.distances[valve][*next] + 1;
+            if cost >= remaining {
+                continue;
+            }
+            let left = remaining - cost;
+            self.explore(
+                *next,
+                left,
+                opened | 1 << bit,
+                pressure + left * self.flows[*next],
+                useful,
+                seen,
+                best,
+            );
+        }
+    }
+
+    // An order to open exactly `set` in that gives `pressure`.
+    fn schedule(
+        &self,
+        valve: usize,
+        remaining: u32,
+        set: usize,
+        pressure: u32,
+        useful: &[usize],
+    ) -> Option<Schedule> {
+        if set == 0 {
+            return (pressure == 0).then(Vec::new);
+        }
+        useful.iter().enumerate().find_map(|(bit, next)| {
+            let cost = self.distances[valve][*next] + 1;
+            if set & (1 << bit) == 0 || cost >= remaining {
+                return None;
+            }
+            let left = remaining - cost;
+            let released = left * self.flows[*next];
+            if released > pressure {
+                return None;
+            }
+            let mut rest =
+                self.schedule(*next, left, set ^ (1 << bit), pressure - released, useful)?;
+            rest.insert(0, (left, *next));
+            Some(rest)
+        })
+    }
+
+    // Every agent starts at `start` at the same time and they never open the
+    // same valve, so the answer is the best way to split the valves into one
+    // disjoint set per agent.
+    pub fn best_plan(&self, start: usize, agents: usize, time: u32) -> Plan {
+        let useful = (0..self.names.len())
+            .filter(|v| self.flows[*v] > 0)
+            .collect::<Vec<usize>>();
+        if useful.len() > 24 {
+            panic!("Too many working valves to plan for: {}", useful.len());
+        }
+        let full = (1usize << useful.len()) - 1;
+        let best = self.best_by_set(start, time, &useful);
+
+        // For every set, the best one agent can do with valves from it only,
+        // and the subset it actually opens to get that. A set no agent can
+        // open in time is never better than one of its subsets, and one
+        // that releases nothing might as well be the empty set.
+        let mut within = best
+            .iter()
+            .enumerate()
+            .map(|(set, pressure)| (*pressure, if *pressure == 0 { 0 } else { set }))
+            .collect::<Vec<(u32, usize)>>();
+        for bit in 0..useful.len() {
+            for set in 0..=full {
+                if set & (1 << bit) != 0 && within[set ^ (1 << bit)].0 > within[set].0 {
+                    within[set] = within[set ^ (1 << bit)];
+                }
+            }
+        }
+
+        // Each extra agent takes its pick of the valves the others leave it.
+        let mut totals = within.iter().map(|w| w.0).collect::<Vec<u32>>();
+        let mut splits: Vec<Vec<usize>> = vec![];
+        for _ in 1..agents {
+            let mut next = vec![0; full + 1];
+            let mut split = vec![0; full + 1];
+            for set in 0..=full {
+                let mut mine = set;
+                loop {
+                    let pressure = within[mine].0 + totals[set ^ mine];
+                    if pressure > next[set] {
+                        next[set] = pressure;
+                        split[set] = mine;
+                    }
+                    if mine == 0 {
+                        break;
+                    }
+                    mine = (mine - 1) & set;
+                }
+            }
+            totals = next;
+            splits.push(split);
+        }
+
+        let mut left = full;
+        let mut sets = vec![];
+        for split in splits.iter().rev() {
+            sets.push(within[split[left]].1);
+            left ^= split[left];
+        }
+        sets.push(within[left].1);
+
+        Plan {
+            pressure: totals[full],
+            schedules: sets
+                .iter()
+                .map(|set| {
+                    self.schedule(start, time, *set, best[*set], &useful)
+                        .expect("No schedule for a planned set of valves")
+                })
+                .collect(),
+        }
+    }
+}
+
+fn floyd_warshall(tunnels: &[Vec<usize>]) -> Vec<Vec<u32>> {
+    let count = tunnels.len();
+    let unreachable = u32::MAX / 2;
+    let mut distances = vec![vec![unreachable; count]; count];
+    for (from, to) in tunnels.iter().enumerate() {
+        distances[from][from] = 0;
+        for t in to {
+            distances[from][*t] = 1;
+        }
+    }
+    for via in 0..count {
+        for from in 0..count {
+            for to in 0..count {
+                let through = distances[from][via] + distances[via][to];
+                if through < distances[from][to] {
+                    distances[from][to] = through;
+                }
+            }
+        }
+    }
+    distances
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+// The rocks as the puzzle draws them, in the order they fall.
+pub const DEFAULT_SHAPES: &str = "\
+####
+
+.#.
+###
+.#.
+
+..#
+..#
+###
+
+#
+#
+#
+#
+
+##
+##
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+// A rock as one bitmask per row, bottom row first, with bit `x` set when the
+// rock fills column `x` counted from its left edge.
+#[derive(Clone, Debug)]
+pub struct Shape {
+    rows: Vec<u8>,
+    width: usize,
+}
+
+// Shapes drawn with `#` and `.`, separated by blank lines.
+pub fn parse_shapes(input: &str) -> Vec<Shape> {
+    input
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let lines = block.lines().map(str::trim).collect::<Vec<&str>>();
+            let width = lines.iter().map(|l| l.len()).max().unwrap();
+            if width > 8 {
+                panic!("Shape wider than 8 columns:\n{}", block);
+            }
+            let rows = lines
+                .iter()
+                .rev()
+                .map(|line| {
+                    line.chars().enumerate().fold(0u8, |row, (x, c)| match c {
+                        '#' => row | 1 << x,
+                        '.' => row,
+                        c => panic!("Unexpected {:?} in shape:\n{}", c, block),
+                    })
+                })
+                .collect::<Vec<u8>>();
+            if rows.iter().all(|r| *r == 0) {
+                panic!("Empty shape:\n{}", block);
+            }
+            Shape { rows, width }
+        })
+        .collect()
+}
+
+pub fn parse_jets(input: &str) -> Vec<Direction> {
+    input
+        .chars()
+        .filter_map(|c| match c {
+            '<' => Some(Direction::Left),
+            '>' => Some(Direction::Right),
+            _ => None,
+        })
+        .collect()
+}
+
+// A rock on its way down, with its bottom left corner at `x`, `y`.
+pub struct Falling {
+    pub shape: usize,
+    pub x: usize,
+    pub y: usize,
+}
+
+// Sees the chamber and the current rock after every move, and whether the rock
+// just came to rest.
+pub type Observer<'a> = &'a mut dyn FnMut(&Chamber, &Falling, bool);
+
+pub struct Chamber {
+    width: usize,
+    shapes: Vec<Shape>,
+    jets: Vec<Direction>,
+    // One bitmask per row of settled rock, up to the top of the tower.
+    rows: Vec<u8>,
+    next_shape: usize,
+    next_jet: usize,
+}
+
+impl Chamber {
+    pub fn new(width: usize, shapes: Vec<Shape>, jets: Vec<Direction>) -> Self {
+        if width == 0 || width > 8 {
+            panic!("Chamber width {} doesn't fit in a byte", width);
+        }
+        if shapes.is_empty() || jets.is_empty() {
+            panic!("Need at least one shape and one jet");
+        }
+        if let Some(shape) = shapes.iter().find(|s| s.width + 2 > width) {
+            panic!(
+                "A {} wide shape doesn't fit in a {} wide chamber",
+                shape.width, width
+            );
+        }
+        Chamber {
+            width,
+            shapes,
+            jets,
+            rows: vec![],
+            next_shape: 0,
+            next_jet: 0,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn fits(&self, shape: &Shape, x: usize, y: usize) -> bool {
+        shape.rows.iter().enumerate().all(|(i, row)| {
+            self.rows
+                .get(y + i)
+                .is_none_or(|settled| settled & (row << x) == 0)
+        })
+    }
+
+    pub fn drop_rock(&mut self, mut observe: Option<Observer>) {
+        let shape = self.shapes[self.next_shape].clone();
+        let mut rock = Falling {
+            shape: self.next_shape,
+            x: 2,
+            y: self.height() + 3,
+        };
+        self.next_shape = (self.next_shape + 1) % self.shapes.len();
+
+        loop {
+            let jet = self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+            match jet {
+                Direction::Left if rock.x > 0 && self.fits(&shape, rock.x - 1, rock.y) => {
+                    rock.x -= 1;
+                }
+                Direction::Right
+                    if rock.x + shape.width < self.width
+                        && self.fits(&shape, rock.x + 1, rock.y) =>
+                {
+                    rock.x += 1;
+                }
+                _ => {}
+            }
+
+            if rock.y == 0 || !self.fits(&shape, rock.x, rock.y - 1) {
+                break;
+            }
+            rock.y -= 1;
+            if let Some(observe) = observe.as_deref_mut() {
+                observe(self, &rock, false);
+            }
+        }
+
+        let top = rock.y + shape.rows.len();
+        if top > self.rows.len() {
+            self.rows.resize(top, 0);
+        }
+        for (i, row) in shape.rows.iter().enumerate() {
+            self.rows[rock.y + i] |= row << rock.x;
+        }
+        if let Some(observe) = observe {
+            observe(self, &rock, true);
+        }
+    }
+
+    // How far below the top of the tower each column's highest rock is.
+    fn skyline(&self) -> Vec<usize> {
+        (0..self.width)
+            .map(|x| {
+                self.rows
+                    .iter()
+                    .rev()
+                    .position(|row| row & (1 << x) != 0)
+                    .unwrap_or(self.rows.len())
+            })
+            .collect()
+    }
+
+    // The top `rows` rows of the tower and the space above it, walls and all,
+    // with the falling rock drawn as `@`.
+    pub fn to_grid(&self, falling: Option<&Falling>, rows: usize) -> Vec<Vec<char>> {
+        let falling_top = falling.map_or(0, |f| f.y + self.shapes[f.shape].rows.len());
+        let top = self.height().max(falling_top) + 3;
+        let bottom = top.saturating_sub(rows);
+        let floor = "+".to_string() + &"-".repeat(self.width) + "+";
+
+        let mut grid = (bottom..top)
+            .rev()
+            .map(|y| {
+                let mut row = vec!['|'];
+                row.extend((0..self.width).map(|x| {
+                    let in_rock = falling.is_some_and(|f| {
+                        let shape = &self.shapes[f.shape];
+                        x >= f.x
+                            && y >= f.y
+                            && shape
+                                .rows
+                                .get(y - f.y)
+                                .is_some_and(|r| r & (1 << (x - f.x)) != 0)
+                    });
+                    if in_rock {
+                        '@'
+                    } else if self.rows.get(y).is_some_and(|r| r & (1 << x) != 0) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                }));
+                row.push('|');
+                row
+            })
+            .collect::<Vec<Vec<char>>>();
+        if bottom == 0 {
+            grid.push(floor.chars().collect());
+        }
+        grid
+    }
+}
+
+// Heights of the tower after any number of rocks. The chamber is simulated
+// until its state repeats: the same jet and shape next, and the same skyline
+// relative to the top. From then on it goes round the same cycle forever.
+pub struct Tower {
+    chamber: Chamber,
+    heights: Vec<usize>,
+    seen: HashMap<(usize, usize, Vec<usize>), usize>,
+    cycle: Option<(usize, usize)>,
+}
+
+impl Tower {
+    pub fn new(chamber: Chamber) -> Self {
+        Tower {
+            chamber,
+            heights: vec![0],
+            seen: HashMap::new(),
+            cycle: None,
+        }
+    }
+
+    pub fn height_after(&mut self, rocks: u64) -> u64 {
+        while self.cycle.is_none() && self.heights.len() as u64 <= rocks {
+            self.chamber.drop_rock(None);
+            let dropped = self.heights.len();
+            self.heights.push(self.chamber.height());
+
+            let key = (
+                self.chamber.next_jet,
+                self.chamber.next_shape,
+                self.chamber.skyline(),
+            );
+            match self.seen.get(&key) {
+                Some(start) => self.cycle = Some((*start, dropped - start)),
+                None => {
+                    self.seen.insert(key, dropped);
+                }
+            }
+        }
+
+        match self.cycle {
+            Some((start, length)) if rocks as usize >= start => {
+                let cycles = (rocks - start as u64) / length as u64;
+                let rest = ((rocks - start as u64) % length as u64) as usize;
+                let gain = (self.heights[start + length] - self.heights[start]) as u64;
+                self.heights[start + rest] as u64 + cycles * gain
+            }
+            _ => self.heights[rocks as usize] as u64,
+        }
+    }
+}
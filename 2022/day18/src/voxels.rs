@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+pub type Cube = [i32; 3];
+
+const SIDES: [Cube; 6] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+
+fn neighbour(cube: Cube, side: Cube) -> Cube {
+    [cube[0] + side[0], cube[1] + side[1], cube[2] + side[2]]
+}
+
+// A set of cubes inside a fixed box, one bit per cube.
+#[derive(Clone)]
+pub struct Voxels {
+    min: Cube,
+    size: [usize; 3],
+    bits: Vec<u64>,
+}
+
+impl Voxels {
+    // Room for every cube from `min` to `max`, both included.
+    pub fn new(min: Cube, max: Cube) -> Self {
+        let size = [0, 1, 2].map(|a| (max[a] - min[a] + 1).max(0) as usize);
+        Voxels {
+            min,
+            size,
+            bits: vec![0; (size[0] * size[1] * size[2]).div_ceil(64)],
+        }
+    }
+
+    fn index(&self, cube: Cube) -> Option<usize> {
+        let mut index = 0;
+        for ((c, min), size) in cube.iter().zip(self.min).zip(self.size) {
+            let offset = c - min;
+            if offset < 0 || offset as usize >= size {
+                return None;
+            }
+            index = index * size + offset as usize;
+        }
+        Some(index)
+    }
+
+    pub fn in_bounds(&self, cube: Cube) -> bool {
+        self.index(cube).is_some()
+    }
+
+    pub fn contains(&self, cube: Cube) -> bool {
+        self.index(cube)
+            .is_some_and(|i| self.bits[i / 64] & (1 << (i % 64)) != 0)
+    }
+
+    pub fn insert(&mut self, cube: Cube) {
+        let i = self
+            .index(cube)
+            .unwrap_or_else(|| panic!("Cube {:?} is outside the scan", cube));
+        self.bits[i / 64] |= 1 << (i % 64);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Cube> + '_ {
+        let [_, size_y, size_z] = self.size;
+        (0..self.size[0] * size_y * size_z)
+            .filter(|i| self.bits[i / 64] & (1 << (i % 64)) != 0)
+            .map(move |i| {
+                [
+                    self.min[0] + (i / (size_y * size_z)) as i32,
+                    self.min[1] + (i / size_z % size_y) as i32,
+                    self.min[2] + (i % size_z) as i32,
+                ]
+            })
+    }
+
+    // Every cube in the box reachable from `start` without going through
+    // `walls`, moving face to face.
+    pub fn flood(&self, start: Cube, walls: &Voxels) -> Voxels {
+        let mut filled = Voxels::new(self.min, self.max());
+        let mut stack = vec![start];
+        filled.insert(start);
+        while let Some(cube) = stack.pop() {
+            for side in SIDES {
+                let next = neighbour(cube, side);
+                if filled.in_bounds(next) && !filled.contains(next) && !walls.contains(next) {
+                    filled.insert(next);
+                    stack.push(next);
+                }
+            }
+        }
+        filled
+    }
+
+    fn max(&self) -> Cube {
+        [0, 1, 2].map(|a| self.min[a] + self.size[a] as i32 - 1)
+    }
+}
+
+// An air pocket sealed inside the droplet.
+pub struct Pocket {
+    pub volume: usize,
+    pub min: Cube,
+    pub max: Cube,
+}
+
+pub struct Droplet {
+    pub lava: Voxels,
+    // The air around the droplet, in a box one cube bigger on every side.
+    pub exterior: Voxels,
+}
+
+impl Droplet {
+    pub fn new(cubes: &[Cube]) -> Self {
+        let min = [0, 1, 2].map(|a| cubes.iter().map(|c| c[a]).min().unwrap_or(0) - 1);
+        let max = [0, 1, 2].map(|a| cubes.iter().map(|c| c[a]).max().unwrap_or(0) + 1);
+        let mut lava = Voxels::new(min, max);
+        for cube in cubes {
+            lava.insert(*cube);
+        }
+        let exterior = lava.flood(min, &lava);
+        Droplet { lava, exterior }
+    }
+
+    // Faces of lava that touch something other than lava.
+    pub fn surface_area(&self) -> usize {
+        self.faces(|cube| !self.lava.contains(cube)).len()
+    }
+
+    // Faces of lava the steam outside can reach.
+    pub fn exterior_area(&self) -> usize {
+        self.exterior_faces().len()
+    }
+
+    fn exterior_faces(&self) -> Vec<(Cube, Cube)> {
+        self.faces(|cube| self.exterior.contains(cube))
+    }
+
+    // Each lava cube with the side it faces out of, for every side that
+    // touches a cube `open` accepts.
+    fn faces(&self, open: impl Fn(Cube) -> bool) -> Vec<(Cube, Cube)> {
+        self.lava
+            .iter()
+            .flat_map(|cube| SIDES.map(|side| (cube, side)))
+            .filter(|(cube, side)| open(neighbour(*cube, *side)))
+            .collect()
+    }
+
+    // Air the steam can't reach, split into the separate pockets.
+    pub fn pockets(&self) -> Vec<Pocket> {
+        let mut walls = self.lava.clone();
+        for cube in self.exterior.iter() {
+            walls.insert(cube);
+        }
+        let mut pockets = vec![];
+        for cube in self.lava.iter().flat_map(|c| SIDES.map(|s| neighbour(c, s))) {
+            if walls.contains(cube) {
+                continue;
+            }
+            let pocket = self.lava.flood(cube, &walls);
+            let cubes = pocket.iter().collect::<Vec<Cube>>();
+            for cube in &cubes {
+                walls.insert(*cube);
+            }
+            pockets.push(Pocket {
+                volume: cubes.len(),
+                min: [0, 1, 2].map(|a| cubes.iter().map(|c| c[a]).min().unwrap()),
+                max: [0, 1, 2].map(|a| cubes.iter().map(|c| c[a]).max().unwrap()),
+            });
+        }
+        pockets
+    }
+
+    // The outside surface as triangles, two per exposed face, wound
+    // counter-clockwise seen from outside. Cube `x,y,z` spans `x..x+1` and so
+    // on.
+    pub fn triangles(&self) -> Vec<([i32; 3], [Cube; 3])> {
+        let mut triangles = vec![];
+        for (cube, side) in self.exterior_faces() {
+            let axis = side.iter().position(|s| *s != 0).unwrap();
+            let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+            let mut corners = [(0, 0), (1, 0), (1, 1), (0, 1)].map(|(db, dc)| {
+                let mut corner = cube;
+                corner[axis] += (side[axis] > 0) as i32;
+                corner[b] += db;
+                corner[c] += dc;
+                corner
+            });
+            if side[axis] < 0 {
+                corners.reverse();
+            }
+            triangles.push((side, [corners[0], corners[1], corners[2]]));
+            triangles.push((side, [corners[0], corners[2], corners[3]]));
+        }
+        triangles
+    }
+
+    pub fn to_obj(&self) -> String {
+        let mut vertices: HashMap<Cube, usize> = HashMap::new();
+        let mut obj = String::from("# Exterior surface of the lava droplet\n");
+        let mut faces = String::new();
+        for (_, triangle) in self.triangles() {
+            let ids = triangle.map(|corner| {
+                let next = vertices.len() + 1;
+                *vertices.entry(corner).or_insert_with(|| {
+                    writeln!(obj, "v {} {} {}", corner[0], corner[1], corner[2]).unwrap();
+                    next
+                })
+            });
+            writeln!(faces, "f {} {} {}", ids[0], ids[1], ids[2]).unwrap();
+        }
+        obj + &faces
+    }
+
+    pub fn to_stl(&self) -> String {
+        let mut stl = String::from("solid droplet\n");
+        for (normal, triangle) in self.triangles() {
+            writeln!(
+                stl,
+                "  facet normal {} {} {}",
+                normal[0], normal[1], normal[2]
+            )
+            .unwrap();
+            stl.push_str("    outer loop\n");
+            for corner in triangle {
+                writeln!(
+                    stl,
+                    "      vertex {} {} {}",
+                    corner[0], corner[1], corner[2]
+                )
+                .unwrap();
+            }
+            stl.push_str("    endloop\n  endfacet\n");
+        }
+        stl + "endsolid droplet\n"
+    }
+}
@@ -0,0 +1,222 @@
+// A blueprint for any number of robot types. Every robot collects one unit a
+// minute of the resource it's named after, and `costs[robot][resource]` is
+// what building one takes. The last resource is the one to maximise.
+#[derive(Clone, Debug)]
+pub struct Blueprint {
+    pub id: usize,
+    pub resources: Vec<String>,
+    pub costs: Vec<Vec<usize>>,
+}
+
+// "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. ..."
+// Robot types come in the order the blueprint lists them. Geodes are the
+// target when there are geode robots, otherwise whatever is listed last.
+pub fn parse_blueprint(line: &str) -> Blueprint {
+    let (head, body) = line
+        .split_once(':')
+        .unwrap_or_else(|| panic!("Invalid blueprint {:?}", line));
+    let id = head
+        .trim()
+        .strip_prefix("Blueprint ")
+        .and_then(|id| id.parse().ok())
+        .unwrap_or_else(|| panic!("Invalid blueprint id in {:?}", line));
+
+    let robots = body
+        .split('.')
+        .map(str::trim)
+        .filter(|sentence| !sentence.is_empty())
+        .map(|sentence| {
+            let (robot, costs) = sentence
+                .strip_prefix("Each ")
+                .and_then(|s| s.split_once(" robot costs "))
+                .unwrap_or_else(|| panic!("Invalid robot {:?} in blueprint {}", sentence, id));
+            let costs = costs
+                .split(" and ")
+                .map(|cost| {
+                    let (amount, resource) = cost
+                        .split_once(' ')
+                        .unwrap_or_else(|| panic!("Invalid cost {:?} in blueprint {}", cost, id));
+                    let amount = amount
+                        .parse::<usize>()
+                        .unwrap_or_else(|_| panic!("Invalid cost {:?} in blueprint {}", cost, id));
+                    (resource.to_string(), amount)
+                })
+                .collect::<Vec<(String, usize)>>();
+            (robot.to_string(), costs)
+        })
+        .collect::<Vec<(String, Vec<(String, usize)>)>>();
+
+    let mut resources = robots
+        .iter()
+        .map(|(robot, _)| robot.clone())
+        .collect::<Vec<String>>();
+    if let Some(geode) = resources.iter().position(|r| r == "geode") {
+        let geode = resources.remove(geode);
+        resources.push(geode);
+    }
+
+    let costs = resources
+        .iter()
+        .map(|robot| {
+            let (_, robot_costs) = robots.iter().find(|(r, _)| r == robot).unwrap();
+            let mut costs = vec![0; resources.len()];
+            for (resource, amount) in robot_costs {
+                let index = resources.iter().position(|r| r == resource).unwrap_or_else(|| {
+                    panic!("No robot collects {} in blueprint {}", resource, id)
+                });
+                costs[index] += amount;
+            }
+            costs
+        })
+        .collect();
+
+    Blueprint {
+        id,
+        resources,
+        costs,
+    }
+}
+
+// The most of the target resource a blueprint can collect, with the minute
+// each robot gets built in to get there.
+pub struct Plan {
+    pub collected: usize,
+    pub builds: Vec<(usize, usize)>,
+}
+
+struct Search<'a> {
+    blueprint: &'a Blueprint,
+    minutes: usize,
+    // No point having more robots of a kind than can be spent in a minute.
+    max_spend: Vec<usize>,
+    best: Plan,
+    builds: Vec<(usize, usize)>,
+}
+
+impl Search<'_> {
+    fn target(&self) -> usize {
+        self.blueprint.resources.len() - 1
+    }
+
+    // What could be collected if target robots only cost what's been saved
+    // up, one resource at a time, and every other robot came for free.
+    fn upper_bound(&self, time_left: usize, robots: &[usize], stock: &[usize]) -> usize {
+        let target = self.target();
+        let cost = &self.blueprint.costs[target];
+        let (mut robots, mut stock) = (robots.to_vec(), stock.to_vec());
+        for _ in 0..time_left {
+            let affordable = stock.iter().zip(cost).all(|(s, c)| s >= c);
+            for (s, r) in stock.iter_mut().zip(&robots) {
+                *s += r;
+            }
+            for (r, robot) in robots.iter_mut().enumerate() {
+                if r != target {
+                    *robot += 1;
+                }
+            }
+            if affordable {
+                for (s, c) in stock.iter_mut().zip(cost) {
+                    *s -= c;
+                }
+                robots[target] += 1;
+            }
+        }
+        stock[target]
+    }
+
+    // Minutes to wait until `robot` can be built, if it ever can.
+    fn wait_for(&self, robot: usize, robots: &[usize], stock: &[usize]) -> Option<usize> {
+        self.blueprint.costs[robot]
+            .iter()
+            .enumerate()
+            .map(|(r, cost)| {
+                if stock[r] >= *cost {
+                    Some(0)
+                } else if robots[r] == 0 {
+                    None
+                } else {
+                    Some((cost - stock[r]).div_ceil(robots[r]))
+                }
+            })
+            .try_fold(0, |wait, w| w.map(|w| wait.max(w)))
+    }
+
+    fn explore(&mut self, time_left: usize, robots: &mut Vec<usize>, stock: &mut Vec<usize>) {
+        let target = self.target();
+
+        // Building nothing else from here on.
+        let idle = stock[target] + robots[target] * time_left;
+        if idle > self.best.collected {
+            self.best = Plan {
+                collected: idle,
+                builds: self.builds.clone(),
+            };
+        }
+        if self.upper_bound(time_left, robots, stock) <= self.best.collected {
+            return;
+        }
+
+        // Pick the next robot to build and wait until there's enough for it.
+        for robot in (0..robots.len()).rev() {
+            if robot != target && robots[robot] >= self.max_spend[robot] {
+                continue;
+            }
+            let Some(wait) = self.wait_for(robot, robots, stock) else {
+                continue;
+            };
+            // A robot finished in the last minute collects nothing.
+            if wait + 1 >= time_left {
+                continue;
+            }
+
+            let minute = self.minutes - time_left + wait + 1;
+            let cost = &self.blueprint.costs[robot];
+            for (r, s) in stock.iter_mut().enumerate() {
+                *s = *s + robots[r] * (wait + 1) - cost[r];
+            }
+            robots[robot] += 1;
+            self.builds.push((minute, robot));
+
+            self.explore(time_left - wait - 1, robots, stock);
+
+            self.builds.pop();
+            robots[robot] -= 1;
+            for (r, s) in stock.iter_mut().enumerate() {
+                *s = *s + cost[r] - robots[r] * (wait + 1);
+            }
+        }
+    }
+}
+
+// Starts with a single robot of the first kind.
+pub fn best_plan(blueprint: &Blueprint, minutes: usize) -> Plan {
+    let kinds = blueprint.resources.len();
+    let max_spend = (0..kinds)
+        .map(|r| blueprint.costs.iter().map(|c| c[r]).max().unwrap_or(0))
+        .collect();
+    let mut search = Search {
+        blueprint,
+        minutes,
+        max_spend,
+        best: Plan {
+            collected: 0,
+            builds: vec![],
+        },
+        builds: vec![],
+    };
+    let mut robots = vec![0; kinds];
+    robots[0] = 1;
+    search.explore(minutes, &mut robots, &mut vec![0; kinds]);
+    search.best
+}
+
+// Every blueprint on a thread of its own.
+pub fn best_plans(blueprints: &[Blueprint], minutes: usize) -> Vec<Plan> {
+    std::thread::scope(|scope| {
+        let handles = blueprints
+            .iter()
+            .map(|blueprint| scope.spawn(move || best_plan(blueprint, minutes)))
+            .collect::<Vec<_>>();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
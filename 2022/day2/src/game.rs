@@ -0,0 +1,144 @@
+// Moves in cycle order, each beating the half of the moves listed just before
+// it, wrapping round. Then the opponent's letter, my letter and the score for
+// playing it. Rock-Paper-Scissors-Lizard-Spock goes rock, spock, paper,
+// lizard, scissors.
+pub const DEFAULT_RULES: &str = "\
+rock A X 1
+paper B Y 2
+scissors C Z 3
+outcomes 0 3 6
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+// How to read the second column of the guide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    // The move to play.
+    Move,
+    // How the round should end. With more than three moves the letters count
+    // up from losing to the furthest move back to winning against the
+    // furthest one ahead, with the draw in the middle.
+    Outcome,
+}
+
+struct Move {
+    name: String,
+    opponent: String,
+    mine: String,
+    score: u64,
+}
+
+pub struct Game {
+    moves: Vec<Move>,
+    // Points for losing, drawing and winning.
+    outcomes: [u64; 3],
+}
+
+impl Game {
+    // Blank lines and lines starting with `#` are skipped, and an
+    // `outcomes LOSE DRAW WIN` line sets the points for each result.
+    pub fn parse(rules: &str) -> Self {
+        let mut moves: Vec<Move> = vec![];
+        let mut outcomes = [0, 3, 6];
+        let number = |word: &str, line: &str| {
+            word.parse::<u64>()
+                .unwrap_or_else(|_| panic!("Invalid score {:?} in rule {:?}", word, line))
+        };
+
+        for line in rules.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                ["outcomes", lose, draw, win] => {
+                    outcomes = [number(lose, line), number(draw, line), number(win, line)];
+                }
+                [name, opponent, mine, score] => {
+                    let taken = moves
+                        .iter()
+                        .find(|m| m.name == name || m.opponent == opponent || m.mine == mine);
+                    if let Some(other) = taken {
+                        panic!("Rule {:?} clashes with the one for {}", line, other.name);
+                    }
+                    moves.push(Move {
+                        name: name.to_string(),
+                        opponent: opponent.to_string(),
+                        mine: mine.to_string(),
+                        score: number(score, line),
+                    });
+                }
+                _ => panic!("Invalid rule {:?}", line),
+            }
+        }
+
+        // With an even number of moves some pairs would beat each other.
+        if moves.len().is_multiple_of(2) {
+            panic!(
+                "A cyclic game needs an odd number of moves, not {}",
+                moves.len()
+            );
+        }
+        Game { moves, outcomes }
+    }
+
+    pub fn outcome(&self, mine: usize, theirs: usize) -> Outcome {
+        let n = self.moves.len();
+        match (mine + n - theirs) % n {
+            0 => Outcome::Draw,
+            ahead if ahead <= n / 2 => Outcome::Win,
+            _ => Outcome::Lose,
+        }
+    }
+
+    pub fn score(&self, mine: usize, theirs: usize) -> u64 {
+        let points = match self.outcome(mine, theirs) {
+            Outcome::Lose => self.outcomes[0],
+            Outcome::Draw => self.outcomes[1],
+            Outcome::Win => self.outcomes[2],
+        };
+        self.moves[mine].score + points
+    }
+
+    // My move for a round of the guide, read the given way.
+    fn respond(&self, theirs: usize, letter: &str, strategy: Strategy) -> Option<usize> {
+        let n = self.moves.len();
+        let index = self.moves.iter().position(|m| m.mine == letter)?;
+        Some(match strategy {
+            Strategy::Move => index,
+            Strategy::Outcome => (theirs + index + n - n / 2) % n,
+        })
+    }
+
+    // Every round of the guide as the opponent's move and mine.
+    fn rounds(&self, guide: &str, strategy: Strategy) -> Vec<(usize, usize)> {
+        guide
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let round = match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                    [theirs, mine] => self
+                        .moves
+                        .iter()
+                        .position(|m| m.opponent == theirs)
+                        .and_then(|theirs| Some((theirs, self.respond(theirs, mine, strategy)?))),
+                    _ => None,
+                };
+                round.unwrap_or_else(|| panic!("Line {}: invalid round {:?}", i + 1, line))
+            })
+            .collect()
+    }
+
+    pub fn total(&self, guide: &str, strategy: Strategy) -> u64 {
+        self.rounds(guide, strategy)
+            .iter()
+            .map(|(theirs, mine)| self.score(*mine, *theirs))
+            .sum()
+    }
+}
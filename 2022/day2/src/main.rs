@@ -1,82 +1,19 @@
 use std::fs;
 
-fn part_1(input: &String) -> i32 {
-    let mut score = 0;
+use common::arg_value;
 
-    for line in input.lines() {
-        let round_elements: Vec<&str> = line.split_whitespace().collect();
-        let picked_element_score = match round_elements[1] {
-            "X" =>  1,
-            "Y" =>  2,
-            _ => 3
-        };
-        
-        let to_add: i32 = match round_elements[0] {
-            "A" => {
-                match picked_element_score {
-                    1 => 4,
-                    2 => 8,
-                    _ => 3
-                }
-            },
-            "B" => {
-                match picked_element_score {
-                    1 => 1,
-                    2 => 5,
-                    _ => 9
-                }
-            },
-            _ => {
-                match picked_element_score {
-                    1 => 7,
-                    2 => 2,
-                    _ => 6
-                }
-            }
-        };
+use crate::game::{Game, Strategy, DEFAULT_RULES};
 
-        score += to_add;
-    }
-    score
-}
-
-fn part_2(input: &String) -> i32 {
-    let mut score = 0;
-
-    for line in input.lines() {
-        let round_elements: Vec<&str> = line.split_whitespace().collect();
-        
-        let to_add: i32 = match round_elements[0] {
-            "A" => {
-                match round_elements[1] {
-                    "X" => 0 + 3,
-                    "Y" => 3 + 1,
-                    _ => 6 + 2
-                }
-            },
-            "B" => {
-                match round_elements[1] {
-                    "X" => 0 + 1,
-                    "Y" => 3 + 2,
-                    _ => 6 + 3
-                }
-            },
-            _ => {
-                match round_elements[1] {
-                    "X" => 0 + 2,
-                    "Y" => 3 + 3,
-                    _ => 6 + 1
-                }
-            }
-        };
-
-        score += to_add;
-    }
-    score
-}
+mod game;
 
 fn main() {
     let input = fs::read_to_string("./input.txt").expect("File not loaded");
-    println!("{}", part_1(&input));
-    println!("{}", part_2(&input));
+    // Rock-Paper-Scissors unless `--rules FILE` describes another game.
+    let rules = arg_value("--rules").map_or(DEFAULT_RULES.to_string(), |path| {
+        fs::read_to_string(&path).unwrap_or_else(|e| panic!("Unable to read {}: {}", path, e))
+    });
+    let game = Game::parse(&rules);
+
+    println!("{}", game.total(&input, Strategy::Move));
+    println!("{}", game.total(&input, Strategy::Outcome));
 }
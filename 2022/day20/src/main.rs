@@ -1,28 +1,54 @@
 use std::fs;
 
-fn decode(numbers_input: &Vec<(usize, i64)>, rounds: usize, key: usize) -> i64 {
-    let mut numbers: Vec<(usize, i64)> = numbers_input
-        .clone()
-        .iter_mut()
-        .map(|(index, val)| (index.clone(), val.clone() * key as i64))
-        .collect();
-    let numbers_len = numbers.len();
+use common::parsed_arg;
 
+use crate::ranked::RankedList;
+
+mod ranked;
+
+fn decode(numbers_input: &[i64], rounds: usize, key: i64) -> i64 {
+    let numbers_len = numbers_input.len();
+    if numbers_len < 2 {
+        panic!("Nothing to mix in {} numbers", numbers_len);
+    }
+    // A zero key decrypts every number, and so every coordinate, to 0.
+    if key == 0 {
+        return 0;
+    }
+    // A number moves round the gaps between the other n - 1, so only its
+    // decrypted value modulo n - 1 matters. Reducing it first in i128 keeps
+    // any key from overflowing.
+    let gaps = numbers_len as i128 - 1;
+    let shifts = numbers_input
+        .iter()
+        .map(|val| (*val as i128 * key as i128).rem_euclid(gaps) as usize)
+        .collect::<Vec<usize>>();
+    let mut numbers = RankedList::new(numbers_input.iter().copied());
+
+    // Nodes are numbered in the original order, which is the mixing order.
     for _ in 0..rounds {
-        for index in 0..numbers_len {
-            let current_index = numbers.iter().position(|&e| e.0 == index).unwrap();
-            let potential_index = current_index as i64 + numbers[current_index].1;
-            let new_index = potential_index.rem_euclid(numbers_len as i64 - 1);
-            let val = numbers.remove(current_index as usize);
-            numbers.insert(new_index as usize, val);
+        for (index, shift) in shifts.iter().enumerate() {
+            let new_index = (numbers.rank(index) + shift) % (numbers_len - 1);
+            numbers.move_to(index, new_index);
         }
     }
 
-    let zero = numbers.iter().position(|&e| e.1 == 0).unwrap();
-    return [1000, 2000, 3000]
+    let zero = numbers.rank(
+        (0..numbers_len)
+            .find(|i| *numbers.value(*i) == 0)
+            .expect("No 0 in the file"),
+    );
+    [1000, 2000, 3000]
         .iter()
-        .map(|shift| numbers[(zero + shift) % numbers_len].1)
-        .sum::<i64>();
+        .map(|shift| {
+            let val = *numbers.value(numbers.at((zero + shift) % numbers_len));
+            val.checked_mul(key)
+                .unwrap_or_else(|| panic!("Decrypting {} with key {} overflows", val, key))
+        })
+        .fold(0i64, |sum, coordinate| {
+            sum.checked_add(coordinate)
+                .unwrap_or_else(|| panic!("The grove coordinates overflow"))
+        })
 }
 
 fn main() {
@@ -30,9 +56,18 @@ fn main() {
     let numbers = input
         .lines()
         .map(|line| line.parse::<i64>().unwrap())
-        .enumerate()
         .collect::<Vec<_>>();
 
+    // `--key K` and `--rounds N` decrypt once with those instead.
+    let (key, rounds) = (parsed_arg::<i64>("--key"), parsed_arg::<usize>("--rounds"));
+    if key.is_some() || rounds.is_some() {
+        println!(
+            "{}",
+            decode(&numbers, rounds.unwrap_or(1), key.unwrap_or(1))
+        );
+        return;
+    }
+
     println!("{}", decode(&numbers, 1, 1));
     println!("{}", decode(&numbers, 10, 811589153));
 }
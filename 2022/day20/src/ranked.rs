@@ -0,0 +1,168 @@
+const NIL: usize = usize::MAX;
+
+struct Node<T> {
+    value: T,
+    left: usize,
+    right: usize,
+    parent: usize,
+    size: usize,
+    priority: u64,
+}
+
+// A list kept as an implicit treap: every node knows its parent and the size of
+// its subtree, so finding where a node is and moving it elsewhere both take
+// O(log n) expected. Nodes keep the id they were created with, their index in
+// the starting order, however much they move.
+pub struct RankedList<T> {
+    nodes: Vec<Node<T>>,
+    root: usize,
+}
+
+impl<T> RankedList<T> {
+    pub fn new(values: impl IntoIterator<Item = T>) -> Self {
+        let mut list = RankedList {
+            nodes: vec![],
+            root: NIL,
+        };
+        // Priorities only need to look random; xorshift is plenty.
+        let mut seed: u64 = 0x9e3779b97f4a7c15;
+        for value in values {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            list.nodes.push(Node {
+                value,
+                left: NIL,
+                right: NIL,
+                parent: NIL,
+                size: 1,
+                priority: seed,
+            });
+            let node = list.nodes.len() - 1;
+            list.root = list.merge(list.root, node);
+        }
+        list.set_root(list.root);
+        list
+    }
+
+    pub fn value(&self, node: usize) -> &T {
+        &self.nodes[node].value
+    }
+
+    fn size(&self, node: usize) -> usize {
+        if node == NIL {
+            0
+        } else {
+            self.nodes[node].size
+        }
+    }
+
+    fn update(&mut self, node: usize) {
+        let (left, right) = (self.nodes[node].left, self.nodes[node].right);
+        self.nodes[node].size = 1 + self.size(left) + self.size(right);
+        for child in [left, right] {
+            if child != NIL {
+                self.nodes[child].parent = node;
+            }
+        }
+    }
+
+    fn set_root(&mut self, root: usize) {
+        self.root = root;
+        if root != NIL {
+            self.nodes[root].parent = NIL;
+        }
+    }
+
+    fn merge(&mut self, left: usize, right: usize) -> usize {
+        if left == NIL {
+            return right;
+        }
+        if right == NIL {
+            return left;
+        }
+        if self.nodes[left].priority > self.nodes[right].priority {
+            let merged = self.merge(self.nodes[left].right, right);
+            self.nodes[left].right = merged;
+            self.update(left);
+            left
+        } else {
+            let merged = self.merge(left, self.nodes[right].left);
+            self.nodes[right].left = merged;
+            self.update(right);
+            right
+        }
+    }
+
+    // The first `count` nodes of the tree, and the rest.
+    fn split(&mut self, node: usize, count: usize) -> (usize, usize) {
+        if node == NIL {
+            return (NIL, NIL);
+        }
+        let left_size = self.size(self.nodes[node].left);
+        if count <= left_size {
+            let (first, rest) = self.split(self.nodes[node].left, count);
+            self.nodes[node].left = rest;
+            self.update(node);
+            if first != NIL {
+                self.nodes[first].parent = NIL;
+            }
+            (first, node)
+        } else {
+            let (first, rest) = self.split(self.nodes[node].right, count - left_size - 1);
+            self.nodes[node].right = first;
+            self.update(node);
+            if rest != NIL {
+                self.nodes[rest].parent = NIL;
+            }
+            (node, rest)
+        }
+    }
+
+    // Where the node currently is in the list.
+    pub fn rank(&self, node: usize) -> usize {
+        let mut rank = self.size(self.nodes[node].left);
+        let mut current = node;
+        while self.nodes[current].parent != NIL {
+            let parent = self.nodes[current].parent;
+            if self.nodes[parent].right == current {
+                rank += self.size(self.nodes[parent].left) + 1;
+            }
+            current = parent;
+        }
+        rank
+    }
+
+    // The node at the given position.
+    pub fn at(&self, rank: usize) -> usize {
+        let mut rank = rank;
+        let mut node = self.root;
+        loop {
+            let left_size = self.size(self.nodes[node].left);
+            if rank < left_size {
+                node = self.nodes[node].left;
+            } else if rank == left_size {
+                return node;
+            } else {
+                rank -= left_size + 1;
+                node = self.nodes[node].right;
+            }
+        }
+    }
+
+    // Takes the node out and puts it back so that it ends up at `rank`.
+    pub fn move_to(&mut self, node: usize, rank: usize) {
+        let (before, rest) = self.split(self.root, self.rank(node));
+        let (_, after) = self.split(rest, 1);
+        let root = self.merge(before, after);
+
+        self.nodes[node].left = NIL;
+        self.nodes[node].right = NIL;
+        self.update(node);
+
+        let (before, after) = self.split(root, rank);
+        let before = self.merge(before, node);
+        let root = self.merge(before, after);
+        self.set_root(root);
+    }
+}
@@ -0,0 +1,193 @@
+use num::{BigInt, BigRational, Zero};
+use std::collections::HashMap;
+
+use crate::{Monke, Operator};
+
+// Root's two sides must be equal, and one monkey's number is unknown. The
+// unknown can only sit on one side, so everything else folds to a number and
+// the operations between root and the unknown can be undone one at a time.
+pub struct Equation<'a> {
+    monkes: &'a HashMap<String, Monke>,
+    unknown: &'a str,
+}
+
+fn show(value: &BigRational) -> String {
+    if value.is_integer() {
+        value.numer().to_string()
+    } else {
+        format!("{}/{}", value.numer(), value.denom())
+    }
+}
+
+fn symbol(operator: &Operator) -> &'static str {
+    match operator {
+        Operator::Add => "+",
+        Operator::Sub => "-",
+        Operator::Mul => "*",
+        Operator::Div => "/",
+    }
+}
+
+impl<'a> Equation<'a> {
+    pub fn new(monkes: &'a HashMap<String, Monke>, unknown: &'a str) -> Self {
+        Equation { monkes, unknown }
+    }
+
+    fn monke(&self, name: &str) -> &'a Monke {
+        self.monkes
+            .get(name)
+            .unwrap_or_else(|| panic!("No monkey called {}", name))
+    }
+
+    fn contains_unknown(&self, name: &str) -> bool {
+        name == self.unknown
+            || match self.monke(name) {
+                Monke::Number(_) => false,
+                Monke::Operation { left, right, .. } => {
+                    self.contains_unknown(left) || self.contains_unknown(right)
+                }
+            }
+    }
+
+    // The monkey's number, with `unknown` standing in for the unknown one.
+    // Monkeys only ever divide evenly, so anything else is an error.
+    fn evaluate(&self, name: &str, unknown: Option<&BigRational>) -> Result<BigRational, String> {
+        if name == self.unknown {
+            if let Some(value) = unknown {
+                return Ok(value.clone());
+            }
+        }
+        match self.monke(name) {
+            Monke::Number(n) => Ok(BigRational::from_integer(BigInt::from(*n))),
+            Monke::Operation {
+                left,
+                right,
+                operator,
+            } => {
+                let l = self.evaluate(left, unknown)?;
+                let r = self.evaluate(right, unknown)?;
+                match operator {
+                    Operator::Add => Ok(l + r),
+                    Operator::Sub => Ok(l - r),
+                    Operator::Mul => Ok(l * r),
+                    Operator::Div if r.is_zero() => Err(format!("{} divides by zero", name)),
+                    Operator::Div => {
+                        let quotient = &l / &r;
+                        if !quotient.is_integer() {
+                            return Err(format!(
+                                "{} divides {} by {}, which isn't exact",
+                                name,
+                                show(&l),
+                                show(&r)
+                            ));
+                        }
+                        Ok(quotient)
+                    }
+                }
+            }
+        }
+    }
+
+    // Which of the monkey's operands holds the unknown, the value of the
+    // other one, and whether the unknown is on the left.
+    fn split(&self, name: &str) -> Result<(&'a str, &'a Operator, BigRational, bool), String> {
+        let Monke::Operation {
+            left,
+            right,
+            operator,
+        } = self.monke(name)
+        else {
+            return Err(format!("{} just yells a number", name));
+        };
+        match (self.contains_unknown(left), self.contains_unknown(right)) {
+            (true, true) => Err(format!(
+                "{} appears on both sides of {}",
+                self.unknown, name
+            )),
+            (false, false) => Err(format!("{} doesn't depend on {}", name, self.unknown)),
+            (true, false) => Ok((left, operator, self.evaluate(right, None)?, true)),
+            (false, true) => Ok((right, operator, self.evaluate(left, None)?, false)),
+        }
+    }
+
+    // The monkey's operations with every known part folded into a number.
+    fn expression(&self, name: &str) -> Result<String, String> {
+        if name == self.unknown {
+            return Ok(name.to_string());
+        }
+        let (side, operator, value, on_left) = self.split(name)?;
+        let side = self.expression(side)?;
+        let (l, r) = if on_left {
+            (side, show(&value))
+        } else {
+            (show(&value), side)
+        };
+        Ok(format!("({} {} {})", l, symbol(operator), r))
+    }
+
+    pub fn simplified(&self, root: &str) -> Result<String, String> {
+        let (side, _, value, _) = self.split(root)?;
+        let expression = self.expression(side)?;
+        let expression = expression
+            .strip_prefix('(')
+            .and_then(|e| e.strip_suffix(')'))
+            .unwrap_or(&expression);
+        Ok(format!("{} = {}", expression, show(&value)))
+    }
+
+    // The unknown's number that makes both sides of root equal.
+    pub fn solve(&self, root: &str) -> Result<BigInt, String> {
+        let (mut name, _, mut target, _) = self.split(root)?;
+
+        while name != self.unknown {
+            let (side, operator, value, on_left) = self.split(name)?;
+            target = match (operator, on_left) {
+                (Operator::Add, _) => target - value,
+                (Operator::Sub, true) => target + value,
+                (Operator::Sub, false) => value - target,
+                (Operator::Mul, _) if value.is_zero() => {
+                    return Err(format!("{} multiplies {} by zero", name, self.unknown))
+                }
+                (Operator::Mul, _) => target / value,
+                (Operator::Div, true) => target * value,
+                // Zero over anything but zero is zero, so nothing pins it down.
+                (Operator::Div, false) if target.is_zero() && value.is_zero() => {
+                    return Err(format!(
+                        "{} divides zero, so {} has no unique solution",
+                        name, self.unknown
+                    ))
+                }
+                (Operator::Div, false) if target.is_zero() => {
+                    return Err(format!("{} would have to divide by infinity", name))
+                }
+                (Operator::Div, false) => value / target,
+            };
+            name = side;
+        }
+
+        if !target.is_integer() {
+            return Err(format!(
+                "{} would have to be {}, which isn't a whole number",
+                self.unknown,
+                show(&target)
+            ));
+        }
+        // Check every division along the way comes out even with that number.
+        let Monke::Operation { left, right, .. } = self.monke(root) else {
+            unreachable!()
+        };
+        let l = self.evaluate(left, Some(&target))?;
+        let r = self.evaluate(right, Some(&target))?;
+        if l != r {
+            return Err(format!(
+                "Both sides of {} should match with {} = {}, but got {} and {}",
+                root,
+                self.unknown,
+                show(&target),
+                show(&l),
+                show(&r)
+            ));
+        }
+        Ok(target.to_integer())
+    }
+}
@@ -3,8 +3,12 @@ use nom::{
     sequence::tuple, IResult,
 };
 use std::collections::HashMap;
-use std::fs;
 use std::str;
+use std::{env, fs};
+
+use crate::equation::Equation;
+
+mod equation;
 
 #[derive(Clone)]
 enum Operator {
@@ -26,7 +30,7 @@ enum Monke {
 
 fn solve(monkes: &HashMap<String, Monke>, monke: String) -> i64 {
     match monkes.get_key_value(&monke).unwrap().1 {
-        Monke::Number(n) => n.clone(),
+        Monke::Number(n) => *n,
         Monke::Operation {
             left,
             right,
@@ -44,78 +48,6 @@ fn solve(monkes: &HashMap<String, Monke>, monke: String) -> i64 {
     }
 }
 
-fn solve_2(monkes: &HashMap<String, Monke>) -> i64 {
-    let mut current_human = 1;
-    let flip_sign = if diff_for_given_human(monkes, current_human) > 1 {
-        -1
-    } else {
-        1
-    };
-
-    loop {
-        let current_diff = diff_for_given_human(monkes, current_human) * flip_sign;
-
-        if current_diff == 0 {
-            return current_human;
-        }
-
-        let next_diff = diff_for_given_human(monkes, current_human * 2) * flip_sign;
-
-        if next_diff > 0 {
-            // We found the bounds. Binary search inside it
-            let mut low = current_human;
-            let mut high = current_human * 2;
-
-            while low <= high {
-                let middle = (((high + low) / 2) as f64).floor() as i64;
-
-                let middle_diff = diff_for_given_human(monkes, middle) * flip_sign;
-
-                if middle_diff == 0 {
-                    // Sometimes many numbers can give the same solution (thanks to division)
-                    for h in low..middle {
-                        if diff_for_given_human(monkes, h) == 0 {
-                            return h;
-                        }
-                    }
-                    return middle;
-                } else if middle_diff > 0 {
-                    high = middle;
-                } else {
-                    low = middle;
-                }
-            }
-        } else {
-            current_human *= 2;
-        }
-    }
-}
-
-fn diff_for_given_human(monkes: &HashMap<String, Monke>, human: i64) -> i64 {
-    let (left, right) = match monkes.get_key_value("root").unwrap().1 {
-        Monke::Number(_) => unreachable!(),
-        Monke::Operation { left, right, .. } => (left, right),
-    };
-
-    let mut monkes_temp: HashMap<String, Monke> = HashMap::new();
-    monkes_temp.extend(monkes.into_iter().map(|(k, v)| {
-        (
-            k.clone(),
-            if k == "humn" {
-                Monke::Number(human)
-            } else {
-                v.clone()
-            },
-        )
-    }));
-
-    get_difference(&monkes_temp, left.clone(), right.clone())
-}
-
-fn get_difference(monkes: &HashMap<String, Monke>, key1: String, key2: String) -> i64 {
-    solve(&monkes, key1.clone()) - solve(&monkes, key2.clone())
-}
-
 fn parse_line(line: &str) -> IResult<&str, (String, Monke)> {
     map(
         tuple((
@@ -161,5 +93,17 @@ fn main() {
         .collect::<HashMap<String, Monke>>();
 
     println!("{}", solve(&monkes, String::from("root")));
-    println!("{}", solve_2(&monkes));
+
+    let equation = Equation::new(&monkes, "humn");
+    let human = equation
+        .solve("root")
+        .unwrap_or_else(|e| panic!("No number for humn: {}", e));
+    println!("{}", human);
+
+    if env::args().any(|a| a == "--equation") {
+        let simplified = equation
+            .simplified("root")
+            .unwrap_or_else(|e| panic!("Can't simplify root: {}", e));
+        println!("{}", simplified);
+    }
 }
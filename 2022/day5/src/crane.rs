@@ -0,0 +1,188 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Crane {
+    // Moves crates one at a time, so a moved pile comes out reversed.
+    CrateMover9000,
+    // Moves the whole pile at once, keeping its order.
+    CrateMover9001,
+}
+
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+    // Where the move is in the input, counting from 1.
+    pub line: usize,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+// "move 1 from 2 to 1", one per line, starting at line `first_line`.
+pub fn parse_moves(input: &str, first_line: usize) -> Vec<Move> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, line)| {
+            let line_number = first_line + i;
+            let words = line.split_whitespace().collect::<Vec<&str>>();
+            let number = |word: &str| {
+                word.parse::<usize>()
+                    .unwrap_or_else(|_| panic!("Line {}: invalid move {:?}", line_number, line))
+            };
+            match words[..] {
+                ["move", count, "from", from, "to", to] => Move {
+                    count: number(count),
+                    from: number(from),
+                    to: number(to),
+                    line: line_number,
+                },
+                _ => panic!("Line {}: invalid move {:?}", line_number, line),
+            }
+        })
+        .collect()
+}
+
+// Stacks of crates, bottom first. Labels can be more than one character.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stacks {
+    stacks: Vec<Vec<String>>,
+}
+
+impl Stacks {
+    // The drawing of the stacks, ending with the line of stack numbers. Each
+    // `[label]` belongs to the stack whose number sits under it.
+    pub fn parse(diagram: &str) -> Self {
+        let lines = diagram
+            .lines()
+            .map(|l| l.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+        let (numbers, rows) = lines
+            .split_last()
+            .unwrap_or_else(|| panic!("No stacks in the diagram"));
+
+        // The columns each stack number covers.
+        let mut columns: Vec<(usize, usize)> = vec![];
+        for (x, c) in numbers.iter().enumerate() {
+            match (c.is_ascii_digit(), columns.last_mut()) {
+                (true, Some((_, end))) if *end == x => *end = x + 1,
+                (true, _) => columns.push((x, x + 1)),
+                _ => {}
+            }
+        }
+        let mut stacks = vec![vec![]; columns.len()];
+
+        for (y, row) in rows.iter().enumerate().rev() {
+            let mut x = 0;
+            while x < row.len() {
+                if row[x] != '[' {
+                    if row[x] != ' ' {
+                        panic!("Line {}: unexpected {:?} in the diagram", y + 1, row[x]);
+                    }
+                    x += 1;
+                    continue;
+                }
+                let end = (x..row.len())
+                    .find(|i| row[*i] == ']')
+                    .unwrap_or_else(|| panic!("Line {}: unclosed crate", y + 1));
+                let stack = columns
+                    .iter()
+                    .position(|(start, stop)| *start <= end && x < *stop)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Line {}: crate at column {} has no stack number",
+                            y + 1,
+                            x + 1
+                        )
+                    });
+                stacks[stack].push(
+                    row[x + 1..end]
+                        .iter()
+                        .collect::<String>()
+                        .trim()
+                        .to_string(),
+                );
+                x = end + 1;
+            }
+        }
+        Stacks { stacks }
+    }
+
+    // The top crate of each stack, with a space for an empty one.
+    pub fn tops(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|s| s.last().map_or(" ", |c| c.as_str()))
+            .collect()
+    }
+
+    pub fn apply(&mut self, step: &Move, crane: Crane) -> Result<(), String> {
+        for stack in [step.from, step.to] {
+            if stack == 0 || stack > self.stacks.len() {
+                return Err(format!(
+                    "Line {}: \"{}\" uses stack {}, but there are only {}",
+                    step.line,
+                    step,
+                    stack,
+                    self.stacks.len()
+                ));
+            }
+        }
+        let from = &mut self.stacks[step.from - 1];
+        if step.count > from.len() {
+            return Err(format!(
+                "Line {}: \"{}\" but stack {} only has {} crates",
+                step.line,
+                step,
+                step.from,
+                from.len()
+            ));
+        }
+        let mut pile = from.split_off(from.len() - step.count);
+        if crane == Crane::CrateMover9000 {
+            pile.reverse();
+        }
+        self.stacks[step.to - 1].append(&mut pile);
+        Ok(())
+    }
+
+    // Drawn the way the input draws it, every cell wide enough for the
+    // longest label.
+    pub fn render(&self) -> String {
+        let label_width = self
+            .stacks
+            .iter()
+            .flatten()
+            .map(|c| c.chars().count())
+            .max()
+            .unwrap_or(1);
+        let cell = label_width + 2;
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut lines = (0..height)
+            .rev()
+            .map(|y| {
+                self.stacks
+                    .iter()
+                    .map(|stack| match stack.get(y) {
+                        Some(label) => format!("[{:^width$}]", label, width = label_width),
+                        None => " ".repeat(cell),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>();
+        lines.push(
+            (1..=self.stacks.len())
+                .map(|n| format!("{:^width$}", n, width = cell))
+                .collect::<Vec<String>>()
+                .join(" "),
+        );
+        lines.join("\n") + "\n"
+    }
+}
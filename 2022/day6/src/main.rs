@@ -1,29 +1,95 @@
-use std::collections::HashSet;
-use std::fs;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::{env, str};
+
+use common::{arg_value, parsed_arg};
+
+use crate::marker::{Alphabet, Detector};
+
+mod marker;
+
+// Feeds the signal to every detector a chunk at a time, so it never has to
+// fit in memory. `found` hears about each marker as the detector's index and
+// position, and returns false once it has seen enough.
+fn scan(
+    mut reader: impl Read,
+    alphabet: &Alphabet,
+    detectors: &mut [Detector],
+    mut found: impl FnMut(usize, usize) -> bool,
+) {
+    let mut buffer = vec![0; 1 << 16];
+    // Bytes of a character split across two reads.
+    let mut pending = 0;
+    loop {
+        let read = reader
+            .read(&mut buffer[pending..])
+            .expect("Unable to read the signal");
+        if read == 0 {
+            if pending > 0 {
+                panic!("The signal ends in the middle of a character");
+            }
+            return;
+        }
+        let filled = pending + read;
+        let valid = match str::from_utf8(&buffer[..filled]) {
+            Ok(_) => filled,
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => panic!("The signal isn't valid UTF-8: {}", e),
+        };
+
+        let text = str::from_utf8(&buffer[..valid]).unwrap();
+        for symbol in text.chars().filter_map(|c| alphabet.symbol(c)) {
+            for (i, detector) in detectors.iter_mut().enumerate() {
+                if detector.push(symbol) && !found(i, detector.position()) {
+                    return;
+                }
+            }
+        }
+
+        buffer.copy_within(valid..filled, 0);
+        pending = filled - valid;
+    }
+}
 
 fn main() {
-    let input = fs::read_to_string("./input.txt").unwrap();
-
-    let result_1 = input
-        .chars()
-        .collect::<Vec<char>>()
-        .windows(4)
-        .take_while(|window| {
-            let set: HashSet<&char> = HashSet::from_iter(window.iter());
-            set.len() != 4
-        })
-        .count() + 4;
-
-    let result_2 = input
-        .chars()
-        .collect::<Vec<char>>()
-        .windows(14)
-        .take_while(|window| {
-            let set: HashSet<&char> = HashSet::from_iter(window.iter());
-            set.len() != 14
-        })
-        .count() + 14;
-
-    println!("{}", result_1);
-    println!("{}", result_2);
+    // Start-of-packet and start-of-message markers, or `--window N` for
+    // another size. `--alphabet SYMBOLS` limits what the signal may contain.
+    let windows = parsed_arg::<usize>("--window").map_or(vec![4, 14], |w| vec![w]);
+    let alphabet = Alphabet::new(arg_value("--alphabet").as_deref());
+    let mut detectors = windows
+        .iter()
+        .map(|w| Detector::new(*w))
+        .collect::<Vec<Detector>>();
+
+    // `--stdin` reads the signal from there instead of input.txt.
+    let reader: Box<dyn Read> = if env::args().any(|a| a == "--stdin") {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(File::open("./input.txt").expect("File not loaded"))
+    };
+
+    // `--all` prints every marker as it turns up, window size first, rather
+    // than stopping at the first of each.
+    let all = env::args().any(|a| a == "--all");
+    let mut out = BufWriter::new(io::stdout().lock());
+    let mut first = vec![None; detectors.len()];
+    scan(reader, &alphabet, &mut detectors, |i, position| {
+        if all {
+            writeln!(out, "{} {}", windows[i], position).unwrap();
+            return true;
+        }
+        first[i].get_or_insert(position);
+        first.iter().any(Option::is_none)
+    });
+
+    if !all {
+        for (detector, position) in detectors.iter().zip(first) {
+            match position {
+                Some(position) => writeln!(out, "{}", position).unwrap(),
+                None => {
+                    writeln!(out, "No marker of {} different symbols", detector.window()).unwrap()
+                }
+            }
+        }
+    }
 }
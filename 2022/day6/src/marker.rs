@@ -0,0 +1,92 @@
+use std::collections::{HashMap, VecDeque};
+
+// Which characters make up the signal, each turned into a small number.
+// Whitespace is never part of it.
+pub struct Alphabet {
+    symbols: Option<HashMap<char, usize>>,
+}
+
+impl Alphabet {
+    // Every character of `symbols`, or any character at all when None.
+    pub fn new(symbols: Option<&str>) -> Self {
+        Alphabet {
+            symbols: symbols.map(|s| {
+                s.chars()
+                    .filter(|c| !c.is_whitespace())
+                    .enumerate()
+                    .map(|(i, c)| (c, i))
+                    .collect()
+            }),
+        }
+    }
+
+    pub fn symbol(&self, c: char) -> Option<usize> {
+        if c.is_whitespace() {
+            return None;
+        }
+        match &self.symbols {
+            None => Some(c as usize),
+            Some(symbols) => Some(
+                *symbols
+                    .get(&c)
+                    .unwrap_or_else(|| panic!("{:?} isn't in the alphabet", c)),
+            ),
+        }
+    }
+}
+
+// Watches a stream of symbols for runs of `window` different ones. Each
+// symbol is counted in when it arrives and out when it leaves the window, so
+// checking a position takes constant time.
+pub struct Detector {
+    window: usize,
+    recent: VecDeque<usize>,
+    counts: Vec<usize>,
+    distinct: usize,
+    seen: usize,
+}
+
+impl Detector {
+    pub fn new(window: usize) -> Self {
+        if window == 0 {
+            panic!("A marker needs at least one symbol");
+        }
+        Detector {
+            window,
+            recent: VecDeque::with_capacity(window),
+            counts: vec![],
+            distinct: 0,
+            seen: 0,
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    // How many symbols have gone past so far.
+    pub fn position(&self) -> usize {
+        self.seen
+    }
+
+    // Takes the next symbol, and says whether it completes a marker.
+    pub fn push(&mut self, symbol: usize) -> bool {
+        if symbol >= self.counts.len() {
+            self.counts.resize(symbol + 1, 0);
+        }
+        if self.recent.len() == self.window {
+            let oldest = self.recent.pop_front().unwrap();
+            self.counts[oldest] -= 1;
+            if self.counts[oldest] == 0 {
+                self.distinct -= 1;
+            }
+        }
+        self.recent.push_back(symbol);
+        self.counts[symbol] += 1;
+        if self.counts[symbol] == 1 {
+            self.distinct += 1;
+        }
+        self.seen += 1;
+        self.distinct == self.window
+    }
+}
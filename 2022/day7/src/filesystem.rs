@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+const ROOT: usize = 0;
+
+enum Kind {
+    Dir(BTreeMap<String, usize>),
+    File(usize),
+}
+
+struct Node {
+    name: String,
+    parent: usize,
+    kind: Kind,
+}
+
+// The directory tree a shell transcript explored. Nodes live in one list and
+// point at each other by index; a node always comes after its parent.
+pub struct FileSystem {
+    nodes: Vec<Node>,
+    // Every node's own size, or everything under it for directories.
+    sizes: Vec<usize>,
+}
+
+impl FileSystem {
+    // Replays `cd` and `ls` from the transcript. `cd` takes `/`, `..`, and
+    // relative or absolute paths; directories seen for the first time in a
+    // `cd` rather than an `ls` are made on the way.
+    pub fn parse(transcript: &str) -> Self {
+        let mut fs = FileSystem {
+            nodes: vec![Node {
+                name: String::from("/"),
+                parent: ROOT,
+                kind: Kind::Dir(BTreeMap::new()),
+            }],
+            sizes: vec![],
+        };
+        let mut cwd = ROOT;
+
+        for line in transcript.lines().filter(|l| !l.trim().is_empty()) {
+            let words = line.split_whitespace().collect::<Vec<&str>>();
+            match words[..] {
+                ["$", "cd", path] => {
+                    if path.starts_with('/') {
+                        cwd = ROOT;
+                    }
+                    for part in path.split('/').filter(|p| !p.is_empty() && *p != ".") {
+                        cwd = match part {
+                            ".." => fs.nodes[cwd].parent,
+                            name => fs.add(cwd, name, Kind::Dir(BTreeMap::new())),
+                        };
+                    }
+                }
+                ["$", "ls"] => {}
+                ["dir", name] => {
+                    fs.add(cwd, name, Kind::Dir(BTreeMap::new()));
+                }
+                [size, name] => {
+                    let size = size
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid transcript line {:?}", line));
+                    fs.add(cwd, name, Kind::File(size));
+                }
+                _ => panic!("Invalid transcript line {:?}", line),
+            }
+        }
+
+        fs.sizes = fs
+            .nodes
+            .iter()
+            .map(|node| match node.kind {
+                Kind::File(size) => size,
+                Kind::Dir(_) => 0,
+            })
+            .collect();
+        for id in (1..fs.nodes.len()).rev() {
+            fs.sizes[fs.nodes[id].parent] += fs.sizes[id];
+        }
+        fs
+    }
+
+    // The child called `name`, made if it isn't there yet. Listing the same
+    // directory twice doesn't count its files twice.
+    fn add(&mut self, parent: usize, name: &str, kind: Kind) -> usize {
+        let next = self.nodes.len();
+        let Kind::Dir(children) = &mut self.nodes[parent].kind else {
+            panic!("{} is a file, not a directory", self.path(parent));
+        };
+        let id = *children.entry(name.to_string()).or_insert(next);
+        if id == next {
+            self.nodes.push(Node {
+                name: name.to_string(),
+                parent,
+                kind,
+            });
+        } else if matches!(kind, Kind::Dir(_)) != self.is_dir(id) {
+            panic!("{} is listed as both a file and a directory", self.path(id));
+        }
+        id
+    }
+
+    fn is_dir(&self, id: usize) -> bool {
+        matches!(self.nodes[id].kind, Kind::Dir(_))
+    }
+
+    pub fn size(&self, id: usize) -> usize {
+        self.sizes[id]
+    }
+
+    pub fn path(&self, id: usize) -> String {
+        let mut parts = vec![];
+        let mut current = id;
+        while current != ROOT {
+            parts.push(self.nodes[current].name.as_str());
+            current = self.nodes[current].parent;
+        }
+        parts.reverse();
+        format!("/{}", parts.join("/"))
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(|id| self.is_dir(*id))
+    }
+
+    // Directories whose total size is within `min..=max`.
+    pub fn find(&self, min: usize, max: usize) -> Vec<usize> {
+        self.dirs()
+            .filter(|id| (min..=max).contains(&self.size(*id)))
+            .collect()
+    }
+
+    // The smallest directory that leaves `required` free on a `disk` sized
+    // disk once deleted. Nothing needs deleting if there's room already.
+    pub fn smallest_to_free(&self, disk: usize, required: usize) -> Option<usize> {
+        let used = self.size(ROOT);
+        if used > disk {
+            panic!("{} used on a disk of {}", used, disk);
+        }
+        let missing = (used + required).checked_sub(disk)?;
+        self.dirs()
+            .filter(|id| self.size(*id) >= missing)
+            .min_by_key(|id| self.size(*id))
+    }
+
+    // Every directory's size and path, children before their parents, like `du`.
+    pub fn du(&self) -> String {
+        let mut order = vec![];
+        self.post_order(ROOT, &mut order);
+        let mut out = String::new();
+        for id in order.into_iter().filter(|id| self.is_dir(*id)) {
+            writeln!(out, "{}\t{}", self.size(id), self.path(id)).unwrap();
+        }
+        out
+    }
+
+    // The whole tree drawn the way the puzzle does.
+    pub fn tree(&self) -> String {
+        let mut out = String::new();
+        self.draw(ROOT, 0, &mut out);
+        out
+    }
+
+    fn post_order(&self, id: usize, order: &mut Vec<usize>) {
+        if let Kind::Dir(children) = &self.nodes[id].kind {
+            for child in children.values() {
+                self.post_order(*child, order);
+            }
+        }
+        order.push(id);
+    }
+
+    fn draw(&self, id: usize, depth: usize, out: &mut String) {
+        let node = &self.nodes[id];
+        let indent = "  ".repeat(depth);
+        match &node.kind {
+            Kind::File(size) => {
+                writeln!(out, "{}- {} (file, size={})", indent, node.name, size).unwrap();
+            }
+            Kind::Dir(children) => {
+                writeln!(
+                    out,
+                    "{}- {} (dir, size={})",
+                    indent,
+                    node.name,
+                    self.size(id)
+                )
+                .unwrap();
+                for child in children.values() {
+                    self.draw(*child, depth + 1, out);
+                }
+            }
+        }
+    }
+}
@@ -1,75 +1,151 @@
-use std::fs;
 use std::collections::HashSet;
-use text_io::scan;
+use std::{env, fs};
 
-fn make_moves(moves: &Vec<(String, i32)>, rope: &mut Vec<(i32, i32)>) -> usize {
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    for m in moves {
-        for _ in 0..m.1 {
-            match m.0.as_str() {
-                "U" => {
-                    rope[0].1 += 1;
-                },
-                "D" => {
-                    rope[0].1 -= 1;
-                },
-                "L" => {
-                    rope[0].0 -= 1;
-                },
-                "R" => {
-                    rope[0].0 += 1;
-                },
-                _ => unreachable!()
-            };
+use common::animate::Animation;
+use common::render::{self, Palette};
+use common::{arg_value, parsed_arg};
 
-            for i in 1..rope.len() {
-                if let Some(coordinates) = move_tail(&rope[i], &rope[i - 1]) {
-                    rope[i] = coordinates;
-                } else {
-                    break;
-                }
-            }
+use crate::rope::{parse_moves, Position, Rope};
+
+mod rope;
 
-            visited.insert(*rope.last().unwrap());
+const VIEW: (i32, i32) = (80, 30);
+
+// `observe` sees the rope after every step. Returns how many cells the tail
+// visited.
+fn make_moves(moves: &[(Position, u32)], rope: &mut Rope, mut observe: impl FnMut(&Rope)) -> usize {
+    for (direction, count) in moves {
+        for _ in 0..*count {
+            rope.step(*direction);
+            observe(rope);
         }
     }
 
-    visited.len()
+    rope.visited(rope.len() - 1).len()
 }
 
-fn move_tail(tail: &(i32, i32), head: &(i32, i32)) -> Option<(i32, i32)> {
-    let x_diff = tail.0 - head.0;
-    let y_diff = tail.1 - head.1;
-    let x_disconnected = x_diff.abs() > 1;
-    let y_disconnected = y_diff.abs() > 1;
+// A VIEW sized window onto the rope with its top left corner at `corner`.
+// Knots are numbered from the head, and the first knot drawn on a cell wins.
+fn to_grid(rope: &[Position], visited: &HashSet<Position>, corner: Position) -> Vec<Vec<char>> {
+    (0..VIEW.1)
+        .map(|row| {
+            (0..VIEW.0)
+                .map(|column| {
+                    // Up is positive y in the rope's coordinates.
+                    let cell = (corner.0 + column, corner.1 - row);
+                    match rope.iter().position(|knot| *knot == cell) {
+                        Some(0) => 'H',
+                        Some(i) if i < 10 => (b'0' + i as u8) as char,
+                        Some(_) => '*',
+                        None if cell == (0, 0) => 's',
+                        None if visited.contains(&cell) => '#',
+                        None => '.',
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
 
-    if x_disconnected && y_disconnected {
-        Some(((head.0 + x_diff.clamp(-1, 1)), (head.1 + y_diff.clamp(-1, 1))))
-    } else if x_disconnected {
-        Some(((head.0 + x_diff.clamp(-1, 1)), head.1))
-    } else if y_disconnected {
-        Some((head.0, (head.1 + y_diff.clamp(-1, 1))))
-    } else {
-        None
-    }
+// Every cell a knot visited, `#` on `.`, with the start as `s`. Just big
+// enough to fit the trail.
+fn trail_grid(visited: &HashSet<Position>) -> Vec<Vec<char>> {
+    let min_x = visited.iter().map(|p| p.0).min().unwrap();
+    let max_x = visited.iter().map(|p| p.0).max().unwrap();
+    let min_y = visited.iter().map(|p| p.1).min().unwrap();
+    let max_y = visited.iter().map(|p| p.1).max().unwrap();
+    (min_y..=max_y)
+        .rev()
+        .map(|y| {
+            (min_x..=max_x)
+                .map(|x| match (x, y) {
+                    (0, 0) => 's',
+                    cell if visited.contains(&cell) => '#',
+                    _ => '.',
+                })
+                .collect()
+        })
+        .collect()
 }
 
 fn main() {
     let input = fs::read_to_string("./input.txt").expect("File not loaded");
+    let moves = parse_moves(&input);
 
-    let mut rope_1: Vec<(i32, i32)> = vec![(0,0); 2];
-    let mut rope_2: Vec<(i32, i32)> = vec![(0,0); 10];
+    // `--knots N` swaps the ten knot rope of part 2 for any other length.
+    let knots = parsed_arg::<usize>("--knots").unwrap_or(10);
+    let mut rope_1 = Rope::new(2);
+    let mut rope_2 = Rope::new(knots);
 
-    let instructions: Vec<(String, i32)> = input.lines().map(|line| {
-        let direction: String;
-        let count: u32;
-        scan!(line.bytes() => "{} {}", direction, count);
-        (direction, count as i32)
-    }).collect();
+    let result_1 = make_moves(&moves, &mut rope_1, |_| {});
 
-    let result_1 = make_moves(&instructions, &mut rope_1);
-    let result_2 = make_moves(&instructions, &mut rope_2);
+    // Only the long rope is animated. The window jumps to recentre on the head
+    // whenever it's about to leave.
+    let mut animation = Animation::from_args();
+    let mut corner = (-VIEW.0 / 2, VIEW.1 / 2);
+    let result_2 = make_moves(&moves, &mut rope_2, |rope| {
+        if let Some(animation) = animation.as_mut() {
+            let (x, y) = rope.knots()[0];
+            if x <= corner.0
+                || x >= corner.0 + VIEW.0 - 1
+                || y >= corner.1
+                || y <= corner.1 - VIEW.1 + 1
+            {
+                corner = (x - VIEW.0 / 2, y + VIEW.1 / 2);
+            }
+            animation.push(|| to_grid(rope.knots(), rope.visited(rope.len() - 1), corner));
+        }
+    });
+    drop(animation);
 
     println!("{}", result_1);
     println!("{}", result_2);
+
+    // The long rope's trails: `--trail K` picks the knot (the tail by
+    // default), `--path` lists the cells it moved through, `--ascii` prints
+    // the cells it visited and `--image FILE` saves them as PPM or SVG.
+    let knot = arg_value("--trail").map_or(rope_2.len() - 1, |v| {
+        v.parse()
+            .ok()
+            .filter(|k| *k < rope_2.len())
+            .unwrap_or_else(|| panic!("No knot {:?} in a rope of {}", v, rope_2.len()))
+    });
+    if env::args().any(|a| a == "--path") {
+        let path = rope_2
+            .trail(knot)
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<String>>();
+        println!("{}", path.join(" "));
+    }
+    let show_ascii = env::args().any(|a| a == "--ascii");
+    let image = arg_value("--image");
+    if show_ascii || image.is_some() {
+        let grid = trail_grid(rope_2.visited(knot));
+        if show_ascii {
+            for row in &grid {
+                println!("{}", row.iter().collect::<String>());
+            }
+        }
+        if let Some(path) = image {
+            let palette = Palette::new([20, 20, 30])
+                .with('#', [230, 180, 60])
+                .with('s', [220, 60, 60]);
+            render::save(path, &grid, &palette, 4);
+        }
+    }
+
+    // `--visited-by X,Y` lists the knots of the long rope that were ever there.
+    if let Some(cell) = arg_value("--visited-by") {
+        let (x, y) = cell
+            .split_once(',')
+            .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+            .unwrap_or_else(|| panic!("Invalid cell {:?}, expected X,Y", cell));
+        let knots = rope_2
+            .knots_visiting((x, y))
+            .iter()
+            .map(|k| k.to_string())
+            .collect::<Vec<String>>();
+        println!("{}", knots.join(" "));
+    }
 }
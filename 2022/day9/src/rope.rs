@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+pub type Position = (i32, i32);
+
+// "R 4" style moves, plus "UR 3" and the like to move the head diagonally.
+pub fn parse_moves(input: &str) -> Vec<(Position, u32)> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (direction, count) = line
+                .split_once(' ')
+                .unwrap_or_else(|| panic!("Invalid move {:?}", line));
+            let step: Position = direction.chars().fold((0, 0), |(x, y), c| match c {
+                'U' => (x, y + 1),
+                'D' => (x, y - 1),
+                'L' => (x - 1, y),
+                'R' => (x + 1, y),
+                _ => panic!("Invalid direction {:?} in {:?}", direction, line),
+            });
+            if step.0.abs() > 1 || step.1.abs() > 1 || step == (0, 0) {
+                panic!("Invalid direction {:?} in {:?}", direction, line);
+            }
+            let count = count
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid count in {:?}", line));
+            (step, count)
+        })
+        .collect()
+}
+
+fn move_tail(tail: &Position, head: &Position) -> Option<Position> {
+    let x_diff = tail.0 - head.0;
+    let y_diff = tail.1 - head.1;
+    let x_disconnected = x_diff.abs() > 1;
+    let y_disconnected = y_diff.abs() > 1;
+
+    if x_disconnected && y_disconnected {
+        Some(((head.0 + x_diff.clamp(-1, 1)), (head.1 + y_diff.clamp(-1, 1))))
+    } else if x_disconnected {
+        Some(((head.0 + x_diff.clamp(-1, 1)), head.1))
+    } else if y_disconnected {
+        Some((head.0, (head.1 + y_diff.clamp(-1, 1))))
+    } else {
+        None
+    }
+}
+
+// A rope of any number of knots, all starting on the origin, numbered from
+// the head. Every knot keeps the cells it moved through, in order.
+pub struct Rope {
+    knots: Vec<Position>,
+    trails: Vec<Vec<Position>>,
+    visited: Vec<HashSet<Position>>,
+}
+
+impl Rope {
+    pub fn new(length: usize) -> Self {
+        if length == 0 {
+            panic!("A rope needs at least one knot");
+        }
+        Rope {
+            knots: vec![(0, 0); length],
+            trails: vec![vec![(0, 0)]; length],
+            visited: vec![HashSet::from([(0, 0)]); length],
+        }
+    }
+
+    pub fn knots(&self) -> &[Position] {
+        &self.knots
+    }
+
+    pub fn len(&self) -> usize {
+        self.knots.len()
+    }
+
+    // Every cell the knot has been on, starting with the origin.
+    pub fn trail(&self, knot: usize) -> &[Position] {
+        &self.trails[knot]
+    }
+
+    pub fn visited(&self, knot: usize) -> &HashSet<Position> {
+        &self.visited[knot]
+    }
+
+    // The knots that have ever been on the cell.
+    pub fn knots_visiting(&self, cell: Position) -> Vec<usize> {
+        (0..self.len())
+            .filter(|knot| self.visited[*knot].contains(&cell))
+            .collect()
+    }
+
+    // Moves the head one step and lets the rest follow. Once a knot stays put
+    // so does everything behind it.
+    pub fn step(&mut self, direction: Position) {
+        self.knots[0].0 += direction.0;
+        self.knots[0].1 += direction.1;
+        self.record(0);
+
+        for i in 1..self.knots.len() {
+            match move_tail(&self.knots[i], &self.knots[i - 1]) {
+                Some(coordinates) => {
+                    self.knots[i] = coordinates;
+                    self.record(i);
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn record(&mut self, knot: usize) {
+        self.trails[knot].push(self.knots[knot]);
+        self.visited[knot].insert(self.knots[knot]);
+    }
+}
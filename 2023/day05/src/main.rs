@@ -1,6 +1,9 @@
 use std::cmp::{max, min};
-use std::fs;
 use std::ops::Range;
+use std::{env, fs};
+
+use common::diff::{self, Differential};
+use common::rng::Rng;
 
 fn parse_seeds(line: &str) -> Vec<u64> {
     line.split_once(": ")
@@ -44,7 +47,7 @@ fn process_map_segment(source: Vec<u64>, segment: &Vec<(u64, u64, u64)>) -> Vec<
         .iter()
         .map(|source_value| {
             for (destination_start, source_start, range) in segment.iter() {
-                if (source_start..=&(source_start + range)).contains(&source_value) {
+                if (source_start..&(source_start + range)).contains(&source_value) {
                     let diff = source_value - source_start;
                     return destination_start + diff;
                 }
@@ -61,48 +64,137 @@ fn process_map_segment_with_range(
     let mut stack = source.clone();
     let mut result: Vec<Range<u64>> = vec![];
 
-    while !stack.is_empty() {
-        let mut element = stack.pop().unwrap().clone();
-        let mut result_element: Vec<Range<u64>> = vec![];
-
+    'element: while let Some(element) = stack.pop() {
         for (destination_start, source_start, range) in segment.iter() {
             let source_end = source_start + range;
+            let intersection = max(element.start, *source_start)..min(element.end, source_end);
 
-            if element.end < *source_start || element.start > source_end {
+            if intersection.is_empty() {
                 continue;
             }
 
-            let intersection = max(element.start, *source_start)..min(element.end, source_end);
-
-            if intersection.start != element.start {
-                stack.push(element.start..(intersection.start - 1));
-                element.start = intersection.start;
+            // Whatever sticks out on either side may still hit another line.
+            if element.start < intersection.start {
+                stack.push(element.start..intersection.start);
             }
-
-            if intersection.end != element.end {
-                stack.push((intersection.end + 1)..element.end);
-                element.end = intersection.end;
+            if intersection.end < element.end {
+                stack.push(intersection.end..element.end);
             }
 
-            let diff_start = intersection.start - source_start;
-            let diff_end = intersection.end - intersection.start;
-
-            result_element.push(
-                (destination_start + diff_start)..(destination_start + diff_start + diff_end),
+            result.push(
+                (destination_start + intersection.start - source_start)
+                    ..(destination_start + intersection.end - source_start),
             );
+            continue 'element;
         }
 
-        if result_element.is_empty() {
-            result.push(element);
-        } else {
-            result.append(&mut result_element);
-        }
+        result.push(element);
     }
 
     result
 }
 
+#[derive(Clone, Debug)]
+struct Almanac {
+    seeds: Vec<u64>,
+    maps: Vec<Vec<(u64, u64, u64)>>,
+}
+
+// Small numbers so the reference can walk every seed in every range.
+fn small_almanac(rng: &mut Rng) -> Almanac {
+    let seeds = (0..rng.range(1, 4))
+        .flat_map(|_| [rng.range(0, 100), rng.range(1, 20)])
+        .collect();
+    let maps = (0..rng.range(1, 4))
+        .map(|_| {
+            let mut cuts = (0..rng.range(2, 8))
+                .map(|_| rng.range(0, 120))
+                .collect::<Vec<u64>>();
+            cuts.sort();
+            cuts.dedup();
+            cuts.chunks_exact(2)
+                .map(|pair| (rng.range(0, 120), pair[0], pair[1] - pair[0]))
+                .collect()
+        })
+        .collect();
+    Almanac { seeds, maps }
+}
+
+fn shrink_almanac(almanac: &Almanac) -> Vec<Almanac> {
+    let pairs = almanac
+        .seeds
+        .chunks(2)
+        .map(|p| p.to_vec())
+        .collect::<Vec<_>>();
+    let mut candidates = diff::shrink_vec(&pairs)
+        .into_iter()
+        .map(|pairs| Almanac {
+            seeds: pairs.concat(),
+            maps: almanac.maps.clone(),
+        })
+        .collect::<Vec<Almanac>>();
+    candidates.extend(
+        diff::shrink_vec(&almanac.maps)
+            .into_iter()
+            .map(|maps| Almanac {
+                seeds: almanac.seeds.clone(),
+                maps,
+            }),
+    );
+    candidates.extend(
+        diff::shrink_each(&almanac.maps, |map| diff::shrink_vec(map))
+            .into_iter()
+            .map(|maps| Almanac {
+                seeds: almanac.seeds.clone(),
+                maps,
+            }),
+    );
+    candidates
+}
+
+fn lowest_location_by_seed(almanac: &Almanac) -> Option<u64> {
+    let seeds = seed_ranges(&almanac.seeds)
+        .into_iter()
+        .flatten()
+        .collect::<Vec<u64>>();
+    almanac
+        .maps
+        .iter()
+        .fold(seeds, |source, segment| {
+            process_map_segment(source, segment)
+        })
+        .into_iter()
+        .min()
+}
+
+fn lowest_location_by_range(almanac: &Almanac) -> Option<u64> {
+    almanac
+        .maps
+        .iter()
+        .fold(seed_ranges(&almanac.seeds), |source, segment| {
+            process_map_segment_with_range(source, segment)
+        })
+        .iter()
+        .filter(|r| !r.is_empty())
+        .map(|r| r.start)
+        .min()
+}
+
+fn differential() -> Differential<Almanac, Option<u64>> {
+    Differential::new(
+        "2023/05 seed ranges",
+        small_almanac,
+        lowest_location_by_seed,
+        lowest_location_by_range,
+    )
+    .shrink(shrink_almanac)
+}
+
 fn main() {
+    if env::args().any(|a| a == "--diff") {
+        return differential().run();
+    }
+
     let input: String = fs::read_to_string("./input.txt").expect("File not loaded");
     let almanac_pieces = input.split("\n\n").collect::<Vec<_>>();
     let seeds = parse_seeds(almanac_pieces[0]);
@@ -132,3 +224,13 @@ fn main() {
             .unwrap()
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimised_matches_reference() {
+        differential().check(0, 200).unwrap();
+    }
+}
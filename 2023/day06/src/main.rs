@@ -1,4 +1,7 @@
-use std::fs;
+use std::{env, fs};
+
+use common::diff::{self, Differential};
+use common::generators::generate;
 
 fn parse_line_multiple_numbers(line: String) -> Vec<u64> {
     line.split_once(":")
@@ -28,7 +31,64 @@ fn number_of_ways_to_beat_record(time: u64, record: u64) -> u64 {
         .count() as u64
 }
 
+// Holding for t wins when t * (time - t) > record, i.e. strictly between the
+// roots of t^2 - time * t + record. The float roots are only a first guess and
+// get nudged onto the first and last winning integers.
+fn number_of_ways_to_beat_record_closed_form(time: u64, record: u64) -> u64 {
+    let beats = |t: u64| t < time && t * (time - t) > record;
+    let discriminant = (time * time) as f64 - 4.0 * record as f64;
+    if discriminant < 0.0 {
+        return 0;
+    }
+    let root = discriminant.sqrt();
+    let mut low = ((time as f64 - root) / 2.0).max(0.0) as u64;
+    while low > 0 && beats(low - 1) {
+        low -= 1;
+    }
+    while low <= time / 2 && !beats(low) {
+        low += 1;
+    }
+    if low > time / 2 {
+        return 0;
+    }
+    // Distances are symmetric around time / 2.
+    time - 2 * low + 1
+}
+
+fn differential() -> Differential<(u64, u64), u64> {
+    Differential::new(
+        "2023/06 ways to beat the record",
+        |rng| {
+            let input = generate("2023/06", rng.next_u64(), 4).unwrap();
+            let lines = input
+                .lines()
+                .map(|x| parse_line_multiple_numbers(x.to_string()))
+                .collect::<Vec<Vec<u64>>>();
+            let record = if rng.chance(0.2) {
+                rng.range(0, lines[0][0] * lines[0][0])
+            } else {
+                lines[1][0]
+            };
+            (lines[0][0], record)
+        },
+        |(time, record)| number_of_ways_to_beat_record(*time, *record),
+        |(time, record)| number_of_ways_to_beat_record_closed_form(*time, *record),
+    )
+    .shrink(|(time, record)| {
+        let mut candidates = diff::shrink_u64(*time)
+            .into_iter()
+            .map(|t| (t, *record))
+            .collect::<Vec<(u64, u64)>>();
+        candidates.extend(diff::shrink_u64(*record).into_iter().map(|r| (*time, r)));
+        candidates
+    })
+}
+
 fn main() {
+    if env::args().any(|a| a == "--diff") {
+        return differential().run();
+    }
+
     let input: String = fs::read_to_string("./input.txt").expect("File not loaded");
     let lines = input
         .clone()
@@ -50,11 +110,22 @@ fn main() {
     let ways_to_win_multiple = times
         .iter()
         .zip(records.iter())
-        .map(|(t, r)| number_of_ways_to_beat_record(*t, *r))
+        .map(|(t, r)| number_of_ways_to_beat_record_closed_form(*t, *r))
         .product::<u64>();
 
-    let ways_to_win_record = number_of_ways_to_beat_record(record_time, record_distance);
+    let ways_to_win_record =
+        number_of_ways_to_beat_record_closed_form(record_time, record_distance);
 
     println!("{}", ways_to_win_multiple);
     println!("{}", ways_to_win_record);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimised_matches_reference() {
+        differential().check(0, 200).unwrap();
+    }
+}
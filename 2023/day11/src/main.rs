@@ -67,7 +67,7 @@ fn generate_pairs(elements: &Vec<Coords>) -> Vec<(&Coords, &Coords)> {
 }
 
 fn distance_between_galaxies((x1, y1): Coords, (x2, y2): Coords) -> u64 {
-    ((x1 as i32 - x2 as i32).abs() as u64) + ((y1 as i32 - y2 as i32).abs() as u64)
+    x1.abs_diff(x2) + y1.abs_diff(y2)
 }
 
 fn main() {
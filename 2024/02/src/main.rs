@@ -1,4 +1,7 @@
-use std::fs;
+use std::{env, fs};
+
+use common::diff::{self, Differential};
+use common::generators::generate;
 
 fn is_safe(report: &Vec<u32>, allow_bad_levels: bool) -> bool {
     let mut inc: Option<bool> = None;
@@ -35,20 +38,88 @@ fn is_safe(report: &Vec<u32>, allow_bad_levels: bool) -> bool {
     true
 }
 
-fn main() {
-    let input = fs::read_to_string("./input.txt").expect("File not loaded");
-    let reports: Vec<Vec<u32>> = input
+// Index of the first pair breaking the rules in the given direction, pretending
+// the level at `skip` isn't there.
+fn first_bad_pair(report: &[u32], skip: Option<usize>, increasing: bool) -> Option<usize> {
+    let levels = (0..report.len())
+        .filter(|i| Some(*i) != skip)
+        .collect::<Vec<usize>>();
+    levels.windows(2).map(|w| (w[0], w[1])).find_map(|(i, j)| {
+        let (a, b) = (report[i], report[j]);
+        let ok = if increasing { a < b } else { a > b };
+        if ok && a.abs_diff(b) <= 3 {
+            None
+        } else {
+            Some(i)
+        }
+    })
+}
+
+// With a fixed direction, a single bad level can only be one side of the first
+// bad pair, so there are at most two removals worth trying per direction.
+fn is_safe_dampened(report: &[u32]) -> bool {
+    [true, false].iter().any(
+        |increasing| match first_bad_pair(report, None, *increasing) {
+            None => true,
+            Some(i) => {
+                first_bad_pair(report, Some(i), *increasing).is_none()
+                    || first_bad_pair(report, Some(i + 1), *increasing).is_none()
+            }
+        },
+    )
+}
+
+fn parse(input: &str) -> Vec<Vec<u32>> {
+    input
         .lines()
         .map(|line| {
             line.split(' ')
                 .map(|input| input.parse::<u32>().unwrap())
                 .collect::<_>()
         })
-        .collect::<_>();
+        .collect::<_>()
+}
+
+fn differential() -> Differential<Vec<u32>, bool> {
+    Differential::new(
+        "2024/02 dampened reports",
+        |rng| parse(&generate("2024/02", rng.next_u64(), 1).unwrap()).remove(0),
+        |report: &Vec<u32>| is_safe(report, true),
+        |report: &Vec<u32>| is_safe_dampened(report),
+    )
+    .shrink(|report| {
+        let mut candidates = diff::shrink_vec(report);
+        candidates.extend(diff::shrink_each(report, |level| {
+            diff::shrink_u64(*level as u64)
+                .into_iter()
+                .map(|l| l as u32)
+                .collect()
+        }));
+        candidates
+    })
+}
+
+fn main() {
+    if env::args().any(|a| a == "--diff") {
+        return differential().run();
+    }
+
+    let input = fs::read_to_string("./input.txt").expect("File not loaded");
+    let reports = parse(&input);
 
     let safe_reports = reports.iter().filter(|l| is_safe(l, false)).count();
     println!("{}", safe_reports);
 
-    let dampened_safe_reports = reports.iter().filter(|l| is_safe(l, true)).count();
+    let dampened_safe_reports = reports.iter().filter(|l| is_safe_dampened(l)).count();
     println!("{}", dampened_safe_reports);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimised_matches_reference() {
+        differential().check(0, 200).unwrap();
+    }
+}
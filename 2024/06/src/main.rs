@@ -1,6 +1,9 @@
 use std::collections::HashSet;
 use std::fs;
 
+use common::animate::Animation;
+use common::render::{Frames, Palette};
+
 type Coords = (usize, usize);
 
 #[derive(Clone, Copy)]
@@ -66,6 +69,29 @@ impl Board {
     fn size(&self) -> Coords {
         (self.tiles[0].len(), self.tiles.len())
     }
+
+    fn to_grid(&self, visited_pos: &HashSet<Coords>) -> Vec<Vec<char>> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, tile)| match tile {
+                        _ if self.guard.pos == (x, y) => match self.guard.direction {
+                            GuardDirection::Up => '^',
+                            GuardDirection::Right => '>',
+                            GuardDirection::Down => 'v',
+                            GuardDirection::Left => '<',
+                        },
+                        Tile::Machine => '#',
+                        Tile::Empty if visited_pos.contains(&(x, y)) => 'X',
+                        Tile::Empty => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
 }
 
 fn parse_input(input: String) -> Board {
@@ -97,8 +123,11 @@ fn parse_input(input: String) -> Board {
     Board { tiles, guard }
 }
 
-// None if detected a loop
-fn simulate_walk(base_board: &Board) -> Option<HashSet<Coords>> {
+// None if detected a loop. `observe` sees the board after every step.
+fn simulate_walk(
+    base_board: &Board,
+    mut observe: impl FnMut(&Board, &HashSet<Coords>),
+) -> Option<HashSet<Coords>> {
     let mut board = base_board.clone();
 
     // I could have had only one HashSet, but I am too lazy to transform in the end of the function
@@ -128,6 +157,8 @@ fn simulate_walk(base_board: &Board) -> Option<HashSet<Coords>> {
                     board.guard.direction = board.guard.next_direction();
                 }
             }
+
+            observe(&board, &visited_pos);
         } else {
             break;
         }
@@ -139,7 +170,26 @@ fn simulate_walk(base_board: &Board) -> Option<HashSet<Coords>> {
 fn main() {
     let input = fs::read_to_string("input.txt").expect("File not loaded");
     let board = parse_input(input);
-    let visited_coords = simulate_walk(&board).unwrap();
+    let mut frames = Frames::from_args(
+        Palette::new([20, 20, 30])
+            .with('#', [120, 120, 140])
+            .with('X', [60, 110, 170])
+            .with('^', [230, 80, 60])
+            .with('>', [230, 80, 60])
+            .with('v', [230, 80, 60])
+            .with('<', [230, 80, 60]),
+    );
+    let mut animation = Animation::from_args();
+    let visited_coords = simulate_walk(&board, |board, visited_pos| {
+        if let Some(frames) = frames.as_mut() {
+            frames.push(&board.to_grid(visited_pos));
+        }
+        if let Some(animation) = animation.as_mut() {
+            animation.push(|| board.to_grid(visited_pos));
+        }
+    })
+    .unwrap();
+    drop(animation);
     let result_1 = visited_coords.len();
     println!("{}", result_1);
 
@@ -149,7 +199,7 @@ fn main() {
         .filter(|&&coords| {
             let mut board_aux = board.clone();
             board_aux.tiles[coords.1][coords.0] = Tile::Machine;
-            match simulate_walk(&board_aux) {
+            match simulate_walk(&board_aux, |_, _| {}) {
                 Some(_) => false,
                 None => true,
             }
@@ -16,6 +16,11 @@ fn parse_input(input: String) -> Vec<(usize, Vec<usize>)> {
         .collect::<Vec<_>>()
 }
 
+fn concatenate(a: usize, b: usize) -> Option<usize> {
+    let digits = b.checked_ilog10().unwrap_or(0) + 1;
+    a.checked_mul(10usize.checked_pow(digits)?)?.checked_add(b)
+}
+
 fn possible_permutations(
     (target, operations): &(usize, Vec<usize>),
     use_extra_operator: bool,
@@ -34,34 +39,20 @@ fn possible_permutations(
 
     let tail = &operations[2..].to_vec();
 
-    let regular_operations = possible_permutations(
-        &(
-            *target,
-            [vec![operations[0] + operations[1]], tail.clone()].concat(),
-        ),
-        use_extra_operator,
-    ) + possible_permutations(
-        &(
-            *target,
-            [vec![operations[0] * operations[1]], tail.clone()].concat(),
+    // An overflowing intermediate is already past the target, so it can't match.
+    let with_head = |head: Option<usize>| match head {
+        Some(head) => possible_permutations(
+            &(*target, [vec![head], tail.clone()].concat()),
+            use_extra_operator,
         ),
-        use_extra_operator,
-    );
+        None => 0,
+    };
+
+    let regular_operations = with_head(operations[0].checked_add(operations[1]))
+        + with_head(operations[0].checked_mul(operations[1]));
 
     let extra_operation = if use_extra_operator {
-        let a = operations[0].to_string();
-        let b = operations[1].to_string();
-        possible_permutations(
-            &(
-                *target,
-                [
-                    vec![format!("{a}{b}").parse::<usize>().unwrap()],
-                    tail.clone(),
-                ]
-                .concat(),
-            ),
-            use_extra_operator,
-        )
+        with_head(concatenate(operations[0], operations[1]))
     } else {
         0
     };
@@ -1,6 +1,8 @@
 use std::fs;
 use text_io::scan;
 
+use common::ocr;
+
 #[derive(Debug)]
 struct Machine {
     pos: (usize, usize),
@@ -92,7 +94,18 @@ fn main() {
         machines.iter_mut().for_each(|m| m.step(map_size));
         let map = create_map(&machines, map_size);
         if map.iter().any(|line| line.contains("############")) {
-            display_map(&machines, map_size);
+            // Robots spelling out letters get read; anything else, like the
+            // tree, is drawn for a human to look at.
+            let grid = map
+                .iter()
+                .map(|line| line.chars().collect())
+                .collect::<Vec<Vec<char>>>();
+            let reading = ocr::read_chars(&grid, '#');
+            if reading.is_complete() {
+                println!("{}", reading.text);
+            } else {
+                display_map(&machines, map_size);
+            }
             break;
         }
         i += 1;
@@ -1,5 +1,8 @@
 use std::fs;
 
+use common::animate::Animation;
+use common::render::{Frames, Palette};
+
 type Coords = (usize, usize);
 
 #[derive(Eq, PartialEq)]
@@ -25,6 +28,26 @@ struct Map {
 }
 
 impl Map {
+    fn to_grid(&self) -> Vec<Vec<char>> {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, field)| match field {
+                        _ if self.robot == (x, y) => '@',
+                        Field::Wall => '#',
+                        Field::Item => 'O',
+                        Field::Empty => '.',
+                        Field::ItemBigLeft => '[',
+                        Field::ItemBigRight => ']',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     fn move_robot(&mut self, direction: Direction) {
         let pos = self.robot;
         let next_field: Coords = match direction {
@@ -274,9 +297,35 @@ fn main() {
     let input = fs::read_to_string("input.txt").expect("Unable to read file");
     let (mut map, mut wide_map, directions) = parse_input(input);
 
-    directions.iter().for_each(|d| map.move_robot(*d));
-    println!("{:?}", map_score(&map));
+    // Both warehouses end up in the same frames and animation, the wide one
+    // after the other.
+    let mut frames = Frames::from_args(
+        Palette::new([20, 20, 30])
+            .with('#', [120, 120, 140])
+            .with('O', [200, 150, 80])
+            .with('[', [200, 150, 80])
+            .with(']', [170, 120, 60])
+            .with('@', [230, 80, 60]),
+    );
+
+    let mut animation = Animation::from_args();
+
+    let mut scores = vec![];
+    for map in [&mut map, &mut wide_map] {
+        for direction in directions.iter() {
+            map.move_robot(*direction);
+            if let Some(frames) = frames.as_mut() {
+                frames.push(&map.to_grid());
+            }
+            if let Some(animation) = animation.as_mut() {
+                animation.push(|| map.to_grid());
+            }
+        }
+        scores.push(map_score(map));
+    }
+    drop(animation);
 
-    directions.iter().for_each(|d| wide_map.move_robot(*d));
-    println!("{:?}", map_score(&wide_map));
+    for score in scores {
+        println!("{:?}", score);
+    }
 }
@@ -9,7 +9,7 @@ fn parse_input() -> Vec<Vec<u8>> {
         .collect()
 }
 
-fn max_bank_joltage_for_n_batteries(bank: &Vec<u8>, batteries: usize) -> usize {
+fn max_bank_joltage_for_n_batteries(bank: &Vec<u8>, batteries: usize) -> u64 {
     let mut res: Vec<u8> = vec![];
     let mut latest_largest: (isize, u8) = (-1, 0);
 
@@ -24,11 +24,11 @@ fn max_bank_joltage_for_n_batteries(bank: &Vec<u8>, batteries: usize) -> usize {
         res.push(max_curr);
     }
 
-    res.iter()
-        .rev()
-        .enumerate()
-        .map(|(i, digit)| (10 as usize).pow(i as u32) * *digit as usize)
-        .sum::<usize>()
+    res.iter().fold(0u64, |acc, digit| {
+        acc.checked_mul(10)
+            .and_then(|acc| acc.checked_add(*digit as u64))
+            .unwrap_or_else(|| panic!("{} batteries overflow the joltage", batteries))
+    })
 }
 
 fn main() {
@@ -36,14 +36,14 @@ fn main() {
     let total_outputs_2 = banks
         .iter()
         .map(|b| max_bank_joltage_for_n_batteries(b, 2))
-        .sum::<usize>();
+        .sum::<u64>();
 
     println!("{}", total_outputs_2);
 
     let total_outputs_12 = banks
         .iter()
         .map(|b| max_bank_joltage_for_n_batteries(b, 12))
-        .sum::<usize>();
+        .sum::<u64>();
 
     println!("{}", total_outputs_12);
 }
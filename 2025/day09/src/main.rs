@@ -1,42 +1,148 @@
-use std::collections::BinaryHeap;
-use std::fs::read_to_string;
+use std::collections::VecDeque;
+use std::fs::{read_to_string, write};
 type Point = (isize, isize);
 
-#[derive(Eq, PartialEq, Ord, Copy, Clone)]
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
 struct Rectangle {
     points: (Point, Point),
     area: usize,
 }
 
-impl PartialOrd for Rectangle {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.area.partial_cmp(&other.area)
+impl Rectangle {
+    fn new(a: Point, b: Point) -> Self {
+        let p_1 = (a.0.min(b.0), a.1.min(b.1));
+        let p_2 = (a.0.max(b.0), a.1.max(b.1));
+        let area = ((p_2.0 - p_1.0 + 1) * (p_2.1 - p_1.1 + 1)) as usize;
+        Rectangle {
+            points: (p_1, p_2),
+            area,
+        }
+    }
+
+    fn corners(&self) -> [Point; 4] {
+        let ((x_1, y_1), (x_2, y_2)) = self.points;
+        [(x_1, y_1), (x_2, y_1), (x_2, y_2), (x_1, y_2)]
     }
 }
 
-fn to_segments(points: &Vec<Point>) -> (Vec<(Point, Point)>, Vec<(Point, Point)>) {
-    let mut h_edges: Vec<(Point, Point)> = vec![];
-    let mut v_edges: Vec<(Point, Point)> = vec![];
-
-    for i in 0..(points.len() - 1) {
-        let (x_1, y_1) = points[i];
-        let (x_2, y_2) = points[i + 1];
-        if y_1 == y_2 {
-            h_edges.push(((x_1.min(x_2), y_1), (x_1.max(x_2), y_1)));
-        } else {
-            v_edges.push(((x_1, y_1.min(y_2)), (x_1, y_1.max(y_2))));
+// Every distinct coordinate gets an odd index and the gap after it the next
+// even one, so a single compressed cell never stands for both a polygon edge
+// and the tiles between two edges. Index 0 and the last index are padding
+// outside the polygon for the flood fill.
+struct CompressedGrid {
+    xs: Vec<isize>,
+    ys: Vec<isize>,
+    // prefix[y][x] = number of tiles inside the polygon in [0, y) x [0, x)
+    prefix: Vec<Vec<usize>>,
+}
+
+impl CompressedGrid {
+    fn new(points: &[Point]) -> Self {
+        let mut xs = points.iter().map(|p| p.0).collect::<Vec<isize>>();
+        let mut ys = points.iter().map(|p| p.1).collect::<Vec<isize>>();
+        xs.sort_unstable();
+        xs.dedup();
+        ys.sort_unstable();
+        ys.dedup();
+
+        let width = xs.len() * 2 + 1;
+        let height = ys.len() * 2 + 1;
+        let mut boundary = vec![vec![false; width]; height];
+
+        for i in 0..points.len() {
+            let a = points[i];
+            let b = points[(i + 1) % points.len()];
+            let (c_x_1, c_x_2) = minmax(compress(&xs, a.0), compress(&xs, b.0));
+            let (c_y_1, c_y_2) = minmax(compress(&ys, a.1), compress(&ys, b.1));
+            for row in boundary.iter_mut().take(c_y_2 + 1).skip(c_y_1) {
+                for cell in row.iter_mut().take(c_x_2 + 1).skip(c_x_1) {
+                    *cell = true;
+                }
+            }
+        }
+
+        // A gap between coordinates one apart holds no tiles, so it takes on
+        // the boundary of the cells either side of it. Otherwise the flood
+        // could slip down it between two adjacent edges.
+        for c_x in zero_width_gaps(&xs) {
+            for row in boundary.iter_mut() {
+                row[c_x] = row[c_x] || row[c_x - 1] || row[c_x + 1];
+            }
+        }
+        for c_y in zero_width_gaps(&ys) {
+            let merged = boundary[c_y - 1]
+                .iter()
+                .zip(&boundary[c_y])
+                .zip(&boundary[c_y + 1])
+                .map(|((above, cell), below)| *above || *cell || *below)
+                .collect::<Vec<bool>>();
+            boundary[c_y] = merged;
         }
+
+        let outside = flood_outside(&boundary);
+
+        let mut prefix = vec![vec![0; width + 1]; height + 1];
+        for y in 0..height {
+            for x in 0..width {
+                let inside = if outside[y][x] { 0 } else { 1 };
+                prefix[y + 1][x + 1] = inside + prefix[y][x + 1] + prefix[y + 1][x] - prefix[y][x];
+            }
+        }
+
+        CompressedGrid { xs, ys, prefix }
     }
 
-    let (x_1, y_1) = points[points.len() - 1];
-    let (x_2, y_2) = points[0];
-    if y_1 == y_2 {
-        h_edges.push(((x_1.min(x_2), y_1), (x_1.max(x_2), y_1)));
-    } else {
-        v_edges.push(((x_1, y_1.min(y_2)), (x_1, y_1.max(y_2))));
+    fn contains(&self, rectangle: &Rectangle) -> bool {
+        let ((x_1, y_1), (x_2, y_2)) = rectangle.points;
+        let (c_x_1, c_x_2) = (compress(&self.xs, x_1), compress(&self.xs, x_2) + 1);
+        let (c_y_1, c_y_2) = (compress(&self.ys, y_1), compress(&self.ys, y_2) + 1);
+        let inside = self.prefix[c_y_2][c_x_2] + self.prefix[c_y_1][c_x_1]
+            - self.prefix[c_y_1][c_x_2]
+            - self.prefix[c_y_2][c_x_1];
+        inside == (c_x_2 - c_x_1) * (c_y_2 - c_y_1)
+    }
+}
+
+fn compress(values: &[isize], value: isize) -> usize {
+    values.binary_search(&value).unwrap() * 2 + 1
+}
+
+// Compressed indices of the gaps between consecutive coordinates one apart.
+fn zero_width_gaps(values: &[isize]) -> Vec<usize> {
+    (1..values.len())
+        .filter(|i| values[*i] - values[i - 1] == 1)
+        .map(|i| i * 2)
+        .collect()
+}
+
+fn minmax(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+fn flood_outside(boundary: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let height = boundary.len();
+    let width = boundary[0].len();
+    let mut outside = vec![vec![false; width]; height];
+    let mut queue: VecDeque<(usize, usize)> = VecDeque::from([(0, 0)]);
+    outside[0][0] = true;
+
+    while let Some((x, y)) = queue.pop_front() {
+        let neighbours = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (n_x, n_y) in neighbours {
+            if n_x >= width || n_y >= height || outside[n_y][n_x] || boundary[n_y][n_x] {
+                continue;
+            }
+            outside[n_y][n_x] = true;
+            queue.push_back((n_x, n_y));
+        }
     }
 
-    (h_edges, v_edges)
+    outside
 }
 
 fn parse_input() -> Vec<Point> {
@@ -50,60 +156,63 @@ fn parse_input() -> Vec<Point> {
         .collect()
 }
 
-fn construct_areas(points: &Vec<Point>) -> BinaryHeap<Rectangle> {
-    let mut areas: BinaryHeap<Rectangle> = BinaryHeap::new();
+fn largest_rectangle(points: &[Point], valid: impl Fn(&Rectangle) -> bool) -> Option<Rectangle> {
+    let mut best: Option<Rectangle> = None;
     for i in 0..points.len() {
         for j in (i + 1)..points.len() {
-            let area = (((points[i].0 - points[j].0).abs() + 1)
-                * ((points[i].1 - points[j].1).abs() + 1)) as usize;
-            let a_p_1 = points[i];
-            let a_p_2 = points[j];
-
-            let p_1 = (a_p_1.0.min(a_p_2.0), a_p_1.1.min(a_p_2.1));
-            let p_2 = (a_p_1.0.max(a_p_2.0), a_p_1.1.max(a_p_2.1));
-            areas.push(Rectangle {
-                points: (p_1, p_2),
-                area,
-            })
+            let rectangle = Rectangle::new(points[i], points[j]);
+            if best.is_some_and(|b| b.area >= rectangle.area) || !valid(&rectangle) {
+                continue;
+            }
+            best = Some(rectangle);
         }
     }
 
-    areas
+    best
 }
 
-fn rectangle_inside_boundaries(
-    (x_1, y_1): &Point,
-    (x_2, y_2): &Point,
-    h_edges: &Vec<(Point, Point)>,
-    v_edges: &Vec<(Point, Point)>,
-) -> bool {
-    for ((e_x_1, e_y), (e_x_2, _)) in h_edges {
-        if e_y > y_1 && e_y < y_2 && e_x_1.max(x_1) < e_x_2.min(x_2) {
-            return false;
-        }
-    }
-
-    for ((e_x, e_y_1), (_, e_y_2)) in v_edges {
-        if e_x > x_1 && e_x < x_2 && e_y_1.max(y_1) < e_y_2.min(y_2) {
-            return false;
-        }
-    }
-
-    true
+fn to_svg(points: &[Point], rectangle: &Rectangle) -> String {
+    let min_x = points.iter().map(|p| p.0).min().unwrap();
+    let min_y = points.iter().map(|p| p.1).min().unwrap();
+    let max_x = points.iter().map(|p| p.0).max().unwrap();
+    let max_y = points.iter().map(|p| p.1).max().unwrap();
+    let stroke = ((max_x - min_x).max(max_y - min_y) / 500).max(1);
+
+    let polygon = points
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect::<Vec<String>>()
+        .join(" ");
+    let ((r_x, r_y), (r_x_2, r_y_2)) = rectangle.points;
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n\
+         <polygon points=\"{}\" fill=\"#2e7d32\" stroke=\"#c62828\" stroke-width=\"{}\"/>\n\
+         <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#1565c0\" fill-opacity=\"0.5\" stroke=\"#0d47a1\" stroke-width=\"{}\"/>\n\
+         </svg>\n",
+        min_x - stroke,
+        min_y - stroke,
+        max_x - min_x + 2 * stroke,
+        max_y - min_y + 2 * stroke,
+        polygon,
+        stroke,
+        r_x,
+        r_y,
+        r_x_2 - r_x + 1,
+        r_y_2 - r_y + 1,
+        stroke
+    )
 }
 
 fn main() {
     let points = parse_input();
-    let mut areas = construct_areas(&points);
 
-    println!("{}", areas.peek().unwrap().area);
+    println!("{}", largest_rectangle(&points, |_| true).unwrap().area);
 
-    let (h_boundaries, v_boundaries) = to_segments(&points);
+    let grid = CompressedGrid::new(&points);
+    let rectangle = largest_rectangle(&points, |r| grid.contains(r)).unwrap();
+    println!("{}", rectangle.area);
+    println!("{:?}", rectangle.corners());
 
-    let mut area = areas.pop().unwrap();
-    while !rectangle_inside_boundaries(&area.points.0, &area.points.1, &h_boundaries, &v_boundaries)
-    {
-        area = areas.pop().unwrap();
-    }
-    println!("{}", area.area);
+    write("rectangle.svg", to_svg(&points, &rectangle)).unwrap();
 }
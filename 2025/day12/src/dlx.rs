@@ -0,0 +1,179 @@
+// Dancing Links over one primary column per piece and one secondary column per
+// board cell, so every piece is placed exactly once and every cell at most once.
+pub struct Dlx {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    size: Vec<usize>,
+    covered: Vec<bool>,
+    rows: Vec<(usize, usize)>,
+    row_of: Vec<usize>,
+    primary: usize,
+}
+
+const ROOT: usize = 0;
+
+impl Dlx {
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        let columns = primary + secondary;
+        let mut dlx = Dlx {
+            left: vec![0; columns + 1],
+            right: vec![0; columns + 1],
+            up: (0..=columns).collect(),
+            down: (0..=columns).collect(),
+            column: (0..=columns).collect(),
+            size: vec![0; columns + 1],
+            covered: vec![false; columns + 1],
+            rows: vec![],
+            row_of: vec![usize::MAX; columns + 1],
+            primary,
+        };
+
+        for c in 0..=columns {
+            if c <= primary {
+                dlx.left[c] = if c == 0 { primary } else { c - 1 };
+                dlx.right[c] = if c == primary { 0 } else { c + 1 };
+            } else {
+                dlx.left[c] = c;
+                dlx.right[c] = c;
+            }
+        }
+
+        dlx
+    }
+
+    pub fn add_row(&mut self, piece: usize, position: usize, cells: &[usize]) {
+        let row = self.rows.len();
+        self.rows.push((piece, position));
+
+        let first = self.left.len();
+        for (i, c) in std::iter::once(piece).chain(cells.iter().copied()).enumerate() {
+            let header = c + 1;
+            let node = first + i;
+            self.column.push(header);
+            self.row_of.push(row);
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+            self.left.push(if i == 0 { node } else { node - 1 });
+            self.right.push(first);
+            if i > 0 {
+                self.right[node - 1] = node;
+                self.left[first] = node;
+            }
+        }
+    }
+
+    // Returns the chosen position of every piece. `allowed` sees the positions
+    // picked so far and can veto a candidate for symmetry pruning.
+    pub fn solve(
+        &mut self,
+        slack: usize,
+        allowed: impl Fn(&[Option<usize>], usize, usize) -> bool,
+    ) -> Option<Vec<usize>> {
+        let mut chosen = vec![None; self.primary];
+        if self.search(slack, &mut chosen, &allowed) {
+            Some(chosen.into_iter().map(|c| c.unwrap()).collect())
+        } else {
+            None
+        }
+    }
+
+    fn search(
+        &mut self,
+        slack: usize,
+        chosen: &mut Vec<Option<usize>>,
+        allowed: &impl Fn(&[Option<usize>], usize, usize) -> bool,
+    ) -> bool {
+        if self.right[ROOT] == ROOT {
+            return true;
+        }
+
+        let dead_cells = (self.primary + 1..self.size.len())
+            .filter(|c| !self.covered[*c] && self.size[*c] == 0)
+            .count();
+        if dead_cells > slack {
+            return false;
+        }
+
+        let mut best = self.right[ROOT];
+        let mut c = self.right[best];
+        while c != ROOT {
+            if self.size[c] < self.size[best] {
+                best = c;
+            }
+            c = self.right[c];
+        }
+        if self.size[best] == 0 {
+            return false;
+        }
+
+        self.cover(best);
+        let mut r = self.down[best];
+        while r != best {
+            let (piece, position) = self.rows[self.row_of[r]];
+            if allowed(chosen, piece, position) {
+                chosen[piece] = Some(position);
+                let mut j = self.right[r];
+                while j != r {
+                    self.cover(self.column[j]);
+                    j = self.right[j];
+                }
+
+                if self.search(slack, chosen, allowed) {
+                    return true;
+                }
+
+                let mut j = self.left[r];
+                while j != r {
+                    self.uncover(self.column[j]);
+                    j = self.left[j];
+                }
+                chosen[piece] = None;
+            }
+            r = self.down[r];
+        }
+        self.uncover(best);
+
+        false
+    }
+
+    fn cover(&mut self, c: usize) {
+        self.covered[c] = true;
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+        self.covered[c] = false;
+    }
+}
@@ -1,71 +1,288 @@
 use std::fs;
 
+use crate::dlx::Dlx;
+
+mod dlx;
+
+type Cell = (usize, usize);
+
+struct Shape {
+    orientations: Vec<Vec<Cell>>,
+    area: usize,
+    bounds: (usize, usize),
+}
+
 struct Region {
     shape: (usize, usize),
     required_shapes: Vec<usize>,
 }
 
-fn parse_input() -> (Vec<usize>, Vec<Region>) {
+#[derive(Debug)]
+struct Placement {
+    shape: usize,
+    cells: Vec<Cell>,
+}
+
+impl Shape {
+    fn new(cells: Vec<Cell>) -> Self {
+        let mut orientations: Vec<Vec<Cell>> = vec![];
+        let mut current = cells.clone();
+        for _ in 0..4 {
+            current = normalize(current.iter().map(|(x, y)| (*y, usize::MAX - x)).collect());
+            let flipped = normalize(current.iter().map(|(x, y)| (usize::MAX - x, *y)).collect());
+            for orientation in [current.clone(), flipped] {
+                if !orientations.contains(&orientation) {
+                    orientations.push(orientation);
+                }
+            }
+        }
+
+        // The bounds of the orientation that gets placed when packing in boxes,
+        // which is already turned from the input.
+        let placed = &orientations[0];
+        let bounds = (
+            placed.iter().map(|c| c.0).max().map_or(0, |x| x + 1),
+            placed.iter().map(|c| c.1).max().map_or(0, |y| y + 1),
+        );
+
+        Shape {
+            area: cells.len(),
+            bounds,
+            orientations,
+        }
+    }
+}
+
+fn normalize(mut cells: Vec<Cell>) -> Vec<Cell> {
+    let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+    cells
+        .iter_mut()
+        .for_each(|c| *c = (c.0 - min_x, c.1 - min_y));
+    cells.sort();
+    cells
+}
+
+fn parse_input() -> (Vec<Shape>, Vec<Region>) {
     let file = fs::read_to_string("input.txt").unwrap();
 
-    let shape_areas = (0..=5)
-        .map(|i| {
-            let rows_start = 1 + (i * 5);
-
-            let mut area = 0;
-            (rows_start..(rows_start + 3)).for_each(|i| {
-                file.lines().skip(i).next().unwrap().chars().for_each(|c| {
-                    if c == '#' {
-                        area += 1;
-                    }
-                });
-            });
-            area
-        })
-        .collect::<Vec<usize>>();
+    let mut shapes = vec![];
+    let mut regions = vec![];
+
+    for block in file.split("\n\n") {
+        let mut lines = block.lines().filter(|l| !l.trim().is_empty()).peekable();
+        let Some(first) = lines.peek() else {
+            continue;
+        };
+
+        if first.trim_end().ends_with(':') {
+            lines.next();
+            let cells = lines
+                .enumerate()
+                .flat_map(|(y, l)| {
+                    l.chars()
+                        .enumerate()
+                        .filter(|(_, c)| *c == '#')
+                        .map(move |(x, _)| (x, y))
+                })
+                .collect::<Vec<Cell>>();
+            shapes.push(Shape::new(cells));
+            continue;
+        }
 
-    let regions = file
-        .lines()
-        .skip(30)
-        .map(|l| {
-            let (dim_raw, required_raw) = l.split_once(": ").unwrap();
-            let (dim_x, dim_y) = dim_raw.split_once("x").unwrap();
+        for l in lines {
+            let (dim_raw, required_raw) = l.split_once(":").unwrap();
+            let (dim_x, dim_y) = dim_raw.trim().split_once("x").unwrap();
             let required_shapes = required_raw
-                .split(" ")
+                .split_whitespace()
                 .map(|x| x.parse::<usize>().unwrap())
                 .collect::<Vec<usize>>();
-            Region {
+            regions.push(Region {
                 shape: (
                     dim_x.parse::<usize>().unwrap(),
                     dim_y.parse::<usize>().unwrap(),
                 ),
                 required_shapes,
-            }
-        })
-        .collect();
+            });
+        }
+    }
 
-    (shape_areas, regions)
+    // Every region lists one count per shape, in the order the shapes came.
+    for region in &regions {
+        if region.required_shapes.len() != shapes.len() {
+            panic!(
+                "Region {}x{} lists {} shape counts, but there are {} shapes",
+                region.shape.0,
+                region.shape.1,
+                region.required_shapes.len(),
+                shapes.len()
+            );
+        }
+    }
+
+    (shapes, regions)
 }
 
-fn can_fit(shape_areas: &Vec<usize>, region: &Region) -> bool {
+fn fits_by_area(shapes: &[Shape], region: &Region) -> bool {
     let board_area = region.shape.0 * region.shape.1;
     let shapes_area = region
         .required_shapes
         .iter()
         .enumerate()
-        .map(|(i, req)| req * shape_areas[i])
+        .map(|(i, req)| req * shapes[i].area)
         .sum::<usize>();
     shapes_area <= board_area
 }
 
+// When every piece gets its own bounding box there is nothing to search.
+fn pack_in_boxes(shapes: &[Shape], region: &Region) -> Option<Vec<Placement>> {
+    let box_w = shapes.iter().map(|s| s.bounds.0).max().unwrap_or(1).max(1);
+    let box_h = shapes.iter().map(|s| s.bounds.1).max().unwrap_or(1).max(1);
+    let columns = region.shape.0 / box_w;
+    let slots = columns * (region.shape.1 / box_h);
+    if region.required_shapes.iter().sum::<usize>() > slots {
+        return None;
+    }
+
+    let placements = region
+        .required_shapes
+        .iter()
+        .enumerate()
+        .flat_map(|(shape, count)| std::iter::repeat_n(shape, *count))
+        .enumerate()
+        .map(|(slot, shape)| {
+            let (o_x, o_y) = ((slot % columns) * box_w, (slot / columns) * box_h);
+            Placement {
+                shape,
+                cells: shapes[shape].orientations[0]
+                    .iter()
+                    .map(|(x, y)| (x + o_x, y + o_y))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Some(placements)
+}
+
+fn pack(shapes: &[Shape], region: &Region) -> Option<Vec<Placement>> {
+    if !fits_by_area(shapes, region) {
+        return None;
+    }
+    if let Some(placements) = pack_in_boxes(shapes, region) {
+        return Some(placements);
+    }
+
+    let (width, height) = region.shape;
+    let positions = shapes
+        .iter()
+        .map(|shape| {
+            shape
+                .orientations
+                .iter()
+                .flat_map(|orientation| {
+                    let o_w = orientation.iter().map(|c| c.0).max().unwrap_or(0);
+                    let o_h = orientation.iter().map(|c| c.1).max().unwrap_or(0);
+                    (0..height.saturating_sub(o_h)).flat_map(move |o_y| {
+                        (0..width.saturating_sub(o_w)).map(move |o_x| {
+                            orientation
+                                .iter()
+                                .map(|(x, y)| (x + o_x, y + o_y))
+                                .collect::<Vec<Cell>>()
+                        })
+                    })
+                })
+                .collect::<Vec<Vec<Cell>>>()
+        })
+        .collect::<Vec<Vec<Vec<Cell>>>>();
+
+    let pieces = region
+        .required_shapes
+        .iter()
+        .enumerate()
+        .flat_map(|(shape, count)| std::iter::repeat_n(shape, *count))
+        .collect::<Vec<usize>>();
+
+    let mut dlx = Dlx::new(pieces.len(), width * height);
+    for (piece, shape) in pieces.iter().enumerate() {
+        if positions[*shape].is_empty() {
+            return None;
+        }
+        for (position, cells) in positions[*shape].iter().enumerate() {
+            let columns = cells
+                .iter()
+                .map(|(x, y)| pieces.len() + y * width + x)
+                .collect::<Vec<usize>>();
+            dlx.add_row(piece, position, &columns);
+        }
+    }
+
+    // Copies of the same shape are interchangeable, so only accept them with
+    // strictly increasing positions.
+    let twins = pieces
+        .iter()
+        .enumerate()
+        .map(|(piece, shape)| {
+            let previous = (0..piece).rev().find(|p| pieces[*p] == *shape);
+            let next = (piece + 1..pieces.len()).find(|p| pieces[*p] == *shape);
+            (previous, next)
+        })
+        .collect::<Vec<(Option<usize>, Option<usize>)>>();
+
+    let free_cells = width * height;
+    let remaining_area = pieces.iter().map(|s| shapes[*s].area).sum::<usize>();
+    let solution = dlx.solve(free_cells - remaining_area, |chosen, piece, position| {
+        let (previous, next) = twins[piece];
+        previous.is_none_or(|p| chosen[p].is_none_or(|c| c < position))
+            && next.is_none_or(|n| chosen[n].is_none_or(|c| c > position))
+    })?;
+
+    Some(
+        solution
+            .into_iter()
+            .enumerate()
+            .map(|(piece, position)| Placement {
+                shape: pieces[piece],
+                cells: positions[pieces[piece]][position].clone(),
+            })
+            .collect(),
+    )
+}
+
+fn render(region: &Region, placements: &[Placement]) -> String {
+    let mut board = vec![vec!['.'; region.shape.0]; region.shape.1];
+    for (i, placement) in placements.iter().enumerate() {
+        let symbol = (b'A' + (i % 26) as u8) as char;
+        for (x, y) in &placement.cells {
+            board[*y][*x] = symbol;
+        }
+    }
+    board
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 fn main() {
     let (shapes, regions) = parse_input();
 
-    let result = regions
+    let packed = regions
         .iter()
-        .map(|region| can_fit(&shapes, region))
-        .filter(|x| *x)
-        .count();
+        .map(|region| (region, pack(&shapes, region)))
+        .collect::<Vec<(&Region, Option<Vec<Placement>>)>>();
+
+    if std::env::args().any(|a| a == "--show") {
+        for (region, placements) in &packed {
+            if let Some(placements) = placements {
+                let shapes_used = placements.iter().map(|p| p.shape).collect::<Vec<usize>>();
+                println!("{}x{} {:?}", region.shape.0, region.shape.1, shapes_used);
+                println!("{}\n", render(region, placements));
+            }
+        }
+    }
+
+    let result = packed.iter().filter(|(_, p)| p.is_some()).count();
 
     println!("{}", result)
 }
@@ -0,0 +1,218 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::thread;
+use std::time::Duration;
+
+use crate::arg_value;
+
+// Replays a simulation in the terminal and/or records it as an asciicast v2
+// file. Only the cells that changed since the previous frame are redrawn, so
+// big boards where a single robot moves stay cheap to play back.
+pub struct Animation {
+    fps: f64,
+    steps: Range<usize>,
+    step: usize,
+    frame: usize,
+    previous: Vec<Vec<char>>,
+    terminal: bool,
+    cast: Option<Cast>,
+}
+
+// The recording is only written out at the end, once the header can hold the
+// size of the biggest frame.
+struct Cast {
+    file: BufWriter<File>,
+    size: (usize, usize),
+    events: Vec<String>,
+}
+
+impl Animation {
+    pub fn new(fps: f64) -> Self {
+        Animation {
+            fps,
+            steps: 0..usize::MAX,
+            step: 0,
+            frame: 0,
+            previous: vec![],
+            terminal: false,
+            cast: None,
+        }
+    }
+
+    // Animation for a day's `--animate` (play in the terminal) and `--cast FILE`
+    // options, with `--fps N` and `--steps FROM..TO` to tune them. None when
+    // neither was asked for.
+    pub fn from_args() -> Option<Self> {
+        let terminal = env::args().any(|a| a == "--animate");
+        let cast = arg_value("--cast");
+        if !terminal && cast.is_none() {
+            return None;
+        }
+
+        // Frames are a whole 1 / fps apart, so it has to be above zero.
+        let fps = arg_value("--fps").map_or(30.0, |v| match v.parse::<f64>() {
+            Ok(fps) if fps > 0.0 => fps,
+            _ => panic!("Invalid frame rate {:?}", v),
+        });
+        let mut animation = Animation::new(fps).terminal(terminal);
+        if let Some(path) = cast {
+            animation = animation.cast(&path);
+        }
+        if let Some(steps) = arg_value("--steps") {
+            let (from, to) = steps
+                .split_once("..")
+                .unwrap_or_else(|| panic!("Invalid step range {:?}, expected FROM..TO", steps));
+            let bound = |b: &str, default: usize| {
+                if b.is_empty() {
+                    default
+                } else {
+                    b.parse()
+                        .unwrap_or_else(|_| panic!("Invalid step {:?} in {:?}", b, steps))
+                }
+            };
+            animation = animation.steps(bound(from, 0)..bound(to, usize::MAX));
+        }
+        Some(animation)
+    }
+
+    pub fn terminal(mut self, terminal: bool) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    pub fn cast(mut self, path: &str) -> Self {
+        let file =
+            File::create(path).unwrap_or_else(|e| panic!("Unable to create {}: {}", path, e));
+        self.cast = Some(Cast {
+            file: BufWriter::new(file),
+            size: (0, 0),
+            events: vec![],
+        });
+        self
+    }
+
+    pub fn steps(mut self, steps: Range<usize>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    // Every simulation step calls this; `draw` only runs for the steps in range.
+    pub fn push(&mut self, draw: impl FnOnce() -> Vec<Vec<char>>) {
+        let step = self.step;
+        self.step += 1;
+        if !self.steps.contains(&step) {
+            return;
+        }
+
+        let grid = draw();
+        let output = if self.frame == 0 {
+            Animation::start(&grid)
+        } else {
+            self.changes(&grid)
+        };
+
+        if self.terminal {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(output.as_bytes()).unwrap();
+            stdout.flush().unwrap();
+            thread::sleep(Duration::from_secs_f64(1.0 / self.fps));
+        }
+        if let Some(cast) = self.cast.as_mut() {
+            let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+            cast.size = (cast.size.0.max(width), cast.size.1.max(grid.len() + 1));
+            cast.record(self.frame as f64 / self.fps, &output);
+        }
+
+        self.previous = grid;
+        self.frame += 1;
+    }
+
+    // The first frame clears the screen and draws everything.
+    fn start(grid: &[Vec<char>]) -> String {
+        let mut output = String::from("\x1b[?25l\x1b[2J\x1b[H");
+        for (y, row) in grid.iter().enumerate() {
+            output.push_str(&format!("\x1b[{};1H", y + 1));
+            output.extend(row.iter());
+        }
+        output
+    }
+
+    fn changes(&self, grid: &[Vec<char>]) -> String {
+        let mut output = String::new();
+        for (y, row) in grid.iter().enumerate() {
+            let previous = self.previous.get(y).map_or(&[][..], |r| &r[..]);
+            let width = row.len().max(previous.len());
+            let changed = |x: usize| row.get(x) != previous.get(x);
+            let mut x = 0;
+            while x < width {
+                if !changed(x) {
+                    x += 1;
+                    continue;
+                }
+                output.push_str(&format!("\x1b[{};{}H", y + 1, x + 1));
+                while x < width && changed(x) {
+                    output.push(row.get(x).copied().unwrap_or(' '));
+                    x += 1;
+                }
+            }
+        }
+        for y in grid.len()..self.previous.len() {
+            output.push_str(&format!("\x1b[{};1H\x1b[2K", y + 1));
+        }
+        output
+    }
+}
+
+impl Drop for Animation {
+    // Leaves the cursor below the last frame, visible again.
+    fn drop(&mut self) {
+        if self.frame == 0 {
+            return;
+        }
+        let end = format!("\x1b[{};1H\x1b[?25h", self.previous.len() + 1);
+        if self.terminal {
+            println!("{}", end);
+        }
+        if let Some(cast) = self.cast.as_mut() {
+            cast.record(self.frame as f64 / self.fps, &end);
+            cast.write();
+        }
+    }
+}
+
+impl Cast {
+    fn record(&mut self, time: f64, output: &str) {
+        self.events
+            .push(format!("[{:.6}, \"o\", {}]", time, json_string(output)));
+    }
+
+    fn write(&mut self) {
+        writeln!(
+            self.file,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+            self.size.0, self.size.1
+        )
+        .unwrap();
+        for event in self.events.drain(..) {
+            writeln!(self.file, "{}", event).unwrap();
+        }
+        self.file.flush().unwrap();
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
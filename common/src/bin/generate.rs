@@ -0,0 +1,47 @@
+use std::env;
+use std::process::exit;
+
+use common::generators::{GENERATORS, adder_swaps, generate};
+
+fn usage() -> ! {
+    eprintln!("Usage: generate <year/day> [--seed N] [--size N]");
+    eprintln!("       generate --list");
+    exit(1)
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if args.iter().any(|a| a == "--list") {
+        GENERATORS.iter().for_each(|(name, _)| println!("{}", name));
+        return;
+    }
+
+    let mut puzzle: Option<String> = None;
+    let mut seed: u64 = 0;
+    let mut size: usize = 50;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = args.next().and_then(|s| s.parse().ok()).unwrap_or_else(|| usage()),
+            "--size" => size = args.next().and_then(|s| s.parse().ok()).unwrap_or_else(|| usage()),
+            _ if puzzle.is_none() => puzzle = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let puzzle = puzzle.unwrap_or_else(|| usage());
+    match generate(&puzzle, seed, size) {
+        Some(input) => {
+            print!("{}", input);
+            // Kept off stdout so the output is still just the input.
+            if puzzle == "2024/24" {
+                eprintln!("Swapped: {}", adder_swaps(seed, size).join(","));
+            }
+        }
+        None => {
+            eprintln!("No generator for {}, see --list", puzzle);
+            exit(1)
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt::Debug;
+use std::process::exit;
+
+use crate::arg_value;
+use crate::rng::Rng;
+
+type Shrink<I> = Box<dyn Fn(&I) -> Vec<I>>;
+
+// Runs a slow but obviously correct implementation next to an optimised one on
+// random inputs. The first disagreement is shrunk greedily: any smaller
+// candidate that still disagrees replaces it, until none is left.
+pub struct Differential<I, O> {
+    name: String,
+    generate: Box<dyn Fn(&mut Rng) -> I>,
+    shrink: Shrink<I>,
+    reference: Box<dyn Fn(&I) -> O>,
+    optimised: Box<dyn Fn(&I) -> O>,
+}
+
+#[derive(Debug)]
+pub struct Mismatch<I, O> {
+    pub seed: u64,
+    pub input: I,
+    pub reference: O,
+    pub optimised: O,
+}
+
+impl<I: Clone + Debug, O: PartialEq + Debug> Differential<I, O> {
+    pub fn new(
+        name: &str,
+        generate: impl Fn(&mut Rng) -> I + 'static,
+        reference: impl Fn(&I) -> O + 'static,
+        optimised: impl Fn(&I) -> O + 'static,
+    ) -> Self {
+        Differential {
+            name: name.to_string(),
+            generate: Box::new(generate),
+            shrink: Box::new(|_| vec![]),
+            reference: Box::new(reference),
+            optimised: Box::new(optimised),
+        }
+    }
+
+    pub fn shrink(mut self, shrink: impl Fn(&I) -> Vec<I> + 'static) -> Self {
+        self.shrink = Box::new(shrink);
+        self
+    }
+
+    fn disagreement(&self, input: &I) -> Option<(O, O)> {
+        let reference = (self.reference)(input);
+        let optimised = (self.optimised)(input);
+        if reference == optimised {
+            None
+        } else {
+            Some((reference, optimised))
+        }
+    }
+
+    pub fn check(&self, seed: u64, cases: usize) -> Result<usize, Mismatch<I, O>> {
+        for case in 0..cases as u64 {
+            let mut rng = Rng::new(seed.wrapping_add(case));
+            let input = (self.generate)(&mut rng);
+            if let Some((reference, optimised)) = self.disagreement(&input) {
+                return Err(self.minimise(Mismatch {
+                    seed: seed.wrapping_add(case),
+                    input,
+                    reference,
+                    optimised,
+                }));
+            }
+        }
+        Ok(cases)
+    }
+
+    fn minimise(&self, mut mismatch: Mismatch<I, O>) -> Mismatch<I, O> {
+        'shrinking: loop {
+            for candidate in (self.shrink)(&mismatch.input) {
+                if let Some((reference, optimised)) = self.disagreement(&candidate) {
+                    mismatch.input = candidate;
+                    mismatch.reference = reference;
+                    mismatch.optimised = optimised;
+                    continue 'shrinking;
+                }
+            }
+            return mismatch;
+        }
+    }
+
+    // Entry point for a day's `--diff` mode. Reads `--seed N` and `--cases N`
+    // from the command line and exits with a failure on the first mismatch.
+    pub fn run(&self) {
+        let (seed, cases) = seed_and_cases();
+        match self.check(seed, cases) {
+            Ok(cases) => println!("{}: {} cases agree", self.name, cases),
+            Err(mismatch) => {
+                println!("{}: mismatch for seed {}", self.name, mismatch.seed);
+                println!("  input:     {:?}", mismatch.input);
+                println!("  reference: {:?}", mismatch.reference);
+                println!("  optimised: {:?}", mismatch.optimised);
+                exit(1);
+            }
+        }
+    }
+}
+
+fn seed_and_cases() -> (u64, usize) {
+    let value = |flag: &str| arg_value(flag).and_then(|v| v.parse::<u64>().ok());
+    (
+        value("--seed").unwrap_or(0),
+        value("--cases").unwrap_or(500) as usize,
+    )
+}
+
+// Candidates with one chunk of elements removed, biggest chunks first.
+pub fn shrink_vec<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut candidates = vec![];
+    let mut chunk = items.len() / 2;
+    while chunk > 0 {
+        for start in (0..items.len()).step_by(chunk) {
+            let end = (start + chunk).min(items.len());
+            candidates.push([&items[..start], &items[end..]].concat());
+        }
+        chunk /= 2;
+    }
+    candidates
+}
+
+// Candidates with a single element replaced by one of its own shrinks.
+pub fn shrink_each<T: Clone>(items: &[T], shrink: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    (0..items.len())
+        .flat_map(|i| {
+            shrink(&items[i]).into_iter().map(move |smaller| {
+                let mut candidate = items.to_vec();
+                candidate[i] = smaller;
+                candidate
+            })
+        })
+        .collect()
+}
+
+pub fn shrink_u64(value: u64) -> Vec<u64> {
+    let mut candidates = vec![0, value / 2, value.saturating_sub(1)];
+    candidates.retain(|c| *c < value);
+    candidates.dedup();
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sums that forget anything of 10 or more, against the real thing.
+    fn planted() -> Differential<Vec<u64>, u64> {
+        Differential::new(
+            "planted",
+            |rng| (0..20).map(|_| rng.range(0, 100)).collect(),
+            |items: &Vec<u64>| items.iter().sum(),
+            |items: &Vec<u64>| items.iter().filter(|i| **i < 10).sum(),
+        )
+        .shrink(|items| {
+            let mut candidates = shrink_vec(items);
+            candidates.extend(shrink_each(items, |i| shrink_u64(*i)));
+            candidates
+        })
+    }
+
+    #[test]
+    fn agreeing_solvers_pass() {
+        let same = Differential::new(
+            "same",
+            |rng| rng.range(0, 1000),
+            |n: &u64| n * 2,
+            |n: &u64| n + n,
+        );
+        assert_eq!(same.check(0, 100).unwrap(), 100);
+    }
+
+    #[test]
+    fn mismatch_is_shrunk_to_the_smallest_input() {
+        let mismatch = planted().check(7, 100).unwrap_err();
+        assert_eq!(mismatch.input, vec![10]);
+        assert_eq!(mismatch.reference, 10);
+        assert_eq!(mismatch.optimised, 0);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use crate::rng::Rng;
+
+mod y2022;
+mod y2023;
+mod y2024;
+mod y2025;
+
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+// Every generator takes a `size` knob: roughly the number of lines or the side
+// of the grid it produces. The same puzzle, seed and size always give the same
+// input.
+pub const GENERATORS: &[(&str, Generator)] = &[
+    ("2022/01", y2022::calories),
+    ("2022/02", y2022::strategy_guide),
+    ("2022/04", y2022::section_pairs),
+    ("2022/05", y2022::crate_stacks),
+    ("2022/06", y2022::datastream),
+    ("2022/07", y2022::terminal_output),
+    ("2022/08", y2022::tree_heights),
+    ("2022/09", y2022::rope_moves),
+    ("2022/10", y2022::cpu_program),
+    ("2022/11", y2022::monkey_notes),
+    ("2022/12", y2022::heightmap),
+    ("2022/13", y2022::packet_pairs),
+    ("2022/14", y2022::rock_paths),
+    ("2022/15", y2022::sensors),
+    ("2022/16", y2022::valves),
+    ("2022/17", y2022::jet_pattern),
+    ("2022/18", y2022::lava_cubes),
+    ("2022/19", y2022::blueprints),
+    ("2022/20", y2022::encrypted_file),
+    ("2022/21", y2022::monkey_math),
+    ("2023/05", y2023::almanac),
+    ("2023/06", y2023::boat_races),
+    ("2023/10", y2023::pipe_loop),
+    ("2023/11", y2023::galaxies),
+    ("2023/19", y2023::workflows),
+    ("2024/01", y2024::location_lists),
+    ("2024/02", y2024::reports),
+    ("2024/06", y2024::guard_map),
+    ("2024/07", y2024::calibrations),
+    ("2024/14", y2024::robots),
+    ("2024/15", y2024::warehouse),
+    ("2024/16", y2024::reindeer_maze),
+    ("2024/17", y2024::three_bit_program),
+    ("2024/20", y2024::racetrack),
+    ("2024/24", y2024::adder),
+    ("2025/03", y2025::battery_banks),
+    ("2025/09", y2025::red_tiles),
+    ("2025/12", y2025::present_regions),
+];
+
+pub fn generate(puzzle: &str, seed: u64, size: usize) -> Option<String> {
+    GENERATORS
+        .iter()
+        .find(|(name, _)| *name == puzzle)
+        .map(|(_, generator)| generator(&mut Rng::new(seed), size))
+}
+
+// The wires swapped in the 2024/24 adder for the same seed and size.
+pub fn adder_swaps(seed: u64, size: usize) -> Vec<String> {
+    y2024::adder_with_swaps(&mut Rng::new(seed), size).1
+}
+
+// Random spanning tree over a `width` x `height` lattice of nodes, drawn on a
+// (2 * width - 1) x (2 * height - 1) grid: nodes sit on even coordinates and
+// the cell between two nodes is set when they're connected. The drawn tree has
+// no holes and no cells touching only by a corner, which makes it a good base
+// for mazes, single-track courses and simple closed loops.
+pub(crate) fn spanning_tree(rng: &mut Rng, width: usize, height: usize) -> Vec<Vec<bool>> {
+    let mut grid = vec![vec![false; 2 * width - 1]; 2 * height - 1];
+    let mut stack = vec![(rng.range_usize(0, width), rng.range_usize(0, height))];
+    grid[2 * stack[0].1][2 * stack[0].0] = true;
+
+    while let Some(&(x, y)) = stack.last() {
+        let mut neighbours = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .map(|(d_x, d_y)| (x as isize + d_x, y as isize + d_y))
+            .filter(|(n_x, n_y)| {
+                *n_x >= 0 && *n_y >= 0 && (*n_x as usize) < width && (*n_y as usize) < height
+            })
+            .map(|(n_x, n_y)| (n_x as usize, n_y as usize))
+            .filter(|(n_x, n_y)| !grid[2 * n_y][2 * n_x])
+            .collect::<Vec<(usize, usize)>>();
+
+        if neighbours.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        rng.shuffle(&mut neighbours);
+        let (n_x, n_y) = neighbours[0];
+        grid[2 * n_y][2 * n_x] = true;
+        grid[y + n_y][x + n_x] = true;
+        stack.push((n_x, n_y));
+    }
+
+    grid
+}
+
+// Lattice points of the outline of a region of unit cells, in clockwise order.
+// The region must be connected, hole-free and never touch itself diagonally.
+pub(crate) fn outline(region: &[Vec<bool>]) -> Vec<(usize, usize)> {
+    let inside = |x: isize, y: isize| {
+        x >= 0
+            && y >= 0
+            && (y as usize) < region.len()
+            && (x as usize) < region[0].len()
+            && region[y as usize][x as usize]
+    };
+
+    let mut next: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    for (y, row) in region.iter().enumerate() {
+        for (x, _) in row.iter().enumerate().filter(|(_, c)| **c) {
+            let (i_x, i_y) = (x as isize, y as isize);
+            if !inside(i_x, i_y - 1) {
+                next.insert((x, y), (x + 1, y));
+            }
+            if !inside(i_x + 1, i_y) {
+                next.insert((x + 1, y), (x + 1, y + 1));
+            }
+            if !inside(i_x, i_y + 1) {
+                next.insert((x + 1, y + 1), (x, y + 1));
+            }
+            if !inside(i_x - 1, i_y) {
+                next.insert((x, y + 1), (x, y));
+            }
+        }
+    }
+
+    let start = *next.keys().min().unwrap();
+    let mut points = vec![start];
+    let mut current = next[&start];
+    while current != start {
+        points.push(current);
+        current = next[&current];
+    }
+
+    points
+}
+
+pub(crate) fn render_grid(grid: &[Vec<char>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
@@ -0,0 +1,651 @@
+use std::collections::HashSet;
+
+use super::render_grid;
+use crate::rng::Rng;
+
+pub fn calories(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..rng.range(1, 7))
+                .map(|_| format!("{}\n", rng.range(1000, 60000)))
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn strategy_guide(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{} {}\n", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z'])))
+        .collect()
+}
+
+pub fn section_pairs(rng: &mut Rng, size: usize) -> String {
+    fn section(rng: &mut Rng) -> (u64, u64) {
+        let start = rng.range(1, 100);
+        (start, rng.range(start, 100))
+    }
+
+    (0..size)
+        .map(|_| {
+            let (a, b) = section(rng);
+            let (c, d) = section(rng);
+            format!("{}-{},{}-{}\n", a, b, c, d)
+        })
+        .collect()
+}
+
+// Moves are simulated while generating, so no instruction ever takes more
+// crates than its source stack holds, and no stack ends up empty.
+pub fn crate_stacks(rng: &mut Rng, size: usize) -> String {
+    let stacks_count = 9;
+    let mut stacks = (0..stacks_count)
+        .map(|_| {
+            (0..rng.range(2, 9))
+                .map(|_| (b'A' + rng.range(0, 26) as u8) as char)
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+
+    let height = stacks.iter().map(|s| s.len()).max().unwrap();
+    let mut result = String::new();
+    for row in (0..height).rev() {
+        let line = stacks
+            .iter()
+            .map(|s| match s.get(row) {
+                Some(c) => format!("[{}]", c),
+                None => "   ".to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        result.push_str(&line);
+        result.push('\n');
+    }
+    let labels = (1..=stacks_count)
+        .map(|i| format!(" {} ", i))
+        .collect::<Vec<String>>()
+        .join(" ");
+    result.push_str(&labels);
+    result.push_str("\n\n");
+
+    for _ in 0..size {
+        let sources = (0..stacks_count)
+            .filter(|i| stacks[*i].len() > 1)
+            .collect::<Vec<usize>>();
+        let from = *rng.pick(&sources);
+        let mut to = rng.range_usize(0, stacks_count - 1);
+        if to >= from {
+            to += 1;
+        }
+        let count = rng.range_usize(1, stacks[from].len().min(7));
+        let remaining = stacks[from].len() - count;
+        let moved = stacks[from].split_off(remaining);
+        stacks[to].extend(moved);
+        result.push_str(&format!("move {} from {} to {}\n", count, from + 1, to + 1));
+    }
+
+    result
+}
+
+// Random noise over a small alphabet, with a 14 character marker planted in the
+// middle so both parts have an answer.
+pub fn datastream(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(32);
+    let mut stream = (0..size)
+        .map(|_| (b'a' + rng.range(0, 6) as u8) as char)
+        .collect::<Vec<char>>();
+    let mut marker = ('a'..='z').collect::<Vec<char>>();
+    rng.shuffle(&mut marker);
+    let start = rng.range_usize(size / 2, size - 14);
+    stream[start..start + 14].copy_from_slice(&marker[..14]);
+    stream.into_iter().collect::<String>() + "\n"
+}
+
+pub fn terminal_output(rng: &mut Rng, size: usize) -> String {
+    fn visit(rng: &mut Rng, depth: usize, budget: &mut usize, out: &mut String) {
+        out.push_str("$ ls\n");
+        let dirs = if depth < 6 && *budget > 0 {
+            rng.range_usize(0, 4.min(*budget + 1))
+        } else {
+            0
+        };
+        *budget = budget.saturating_sub(dirs);
+        let mut names = (0..dirs)
+            .map(|i| format!("{}{}", rng.pick(&["a", "b", "d", "e", "f", "g"]), i))
+            .collect::<Vec<String>>();
+        for name in &names {
+            out.push_str(&format!("dir {}\n", name));
+        }
+        for i in 0..rng.range(0, 4) {
+            let extension = rng.pick(&["", ".txt", ".dat", ".log"]);
+            out.push_str(&format!(
+                "{} {}{}{}\n",
+                rng.range(1000, 300000),
+                rng.pick(&["b", "c", "h", "i", "j", "k"]),
+                i,
+                extension
+            ));
+        }
+        rng.shuffle(&mut names);
+        for name in names {
+            out.push_str(&format!("$ cd {}\n", name));
+            visit(rng, depth + 1, budget, out);
+            out.push_str("$ cd ..\n");
+        }
+    }
+
+    let mut out = String::from("$ cd /\n");
+    let mut budget = size;
+    visit(rng, 0, &mut budget, &mut out);
+    out
+}
+
+pub fn tree_heights(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| (b'0' + rng.range(0, 10) as u8) as char)
+                .collect::<String>()
+                + "\n"
+        })
+        .collect()
+}
+
+pub fn rope_moves(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{} {}\n", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1, 20)))
+        .collect()
+}
+
+// At least 240 cycles so the whole CRT gets drawn.
+pub fn cpu_program(rng: &mut Rng, size: usize) -> String {
+    let mut cycles = 0;
+    let mut x: i64 = 1;
+    let mut out = String::new();
+    while cycles < 240.max(size) {
+        if rng.chance(0.3) {
+            out.push_str("noop\n");
+            cycles += 1;
+        } else {
+            let mut value = rng.range_i64(-20, 21);
+            if !(-5..=45).contains(&(x + value)) {
+                value = -value;
+            }
+            x += value;
+            out.push_str(&format!("addx {}\n", value));
+            cycles += 2;
+        }
+    }
+    out
+}
+
+struct Monkey {
+    items: Vec<u64>,
+    // None squares the worry level.
+    operation: (char, Option<u64>),
+    divisor: u64,
+    targets: (usize, usize),
+}
+
+// Part 1 has no modular reduction, so notes whose worry levels leave 64 bits
+// within its 20 rounds are redrawn.
+pub fn monkey_notes(rng: &mut Rng, size: usize) -> String {
+    // The real notes use the first eight primes, whose product squared still
+    // fits into 64 bits.
+    let primes = [2, 3, 5, 7, 11, 13, 17, 19];
+    // Fewer monkeys tend to pass the squared items straight back and forth.
+    let count = size.clamp(4, primes.len());
+
+    let mut attempts = 0;
+    loop {
+        // Adding one never outgrows the relief, so that always fits in the end.
+        attempts += 1;
+        let tame = attempts > 1000;
+        let mut divisors = primes.to_vec();
+        rng.shuffle(&mut divisors);
+        let squaring = rng.range_usize(0, count);
+
+        let monkeys = (0..count)
+            .map(|i| {
+                let operation = match rng.range(0, 5) {
+                    _ if tame => ('+', Some(1)),
+                    _ if i == squaring => ('*', None),
+                    0 | 1 => ('*', Some(rng.range(2, 20))),
+                    _ => ('+', Some(rng.range(1, 9))),
+                };
+                let mut targets = (0..count).filter(|m| *m != i).collect::<Vec<usize>>();
+                rng.shuffle(&mut targets);
+                Monkey {
+                    items: (0..rng.range(1, 6)).map(|_| rng.range(50, 100)).collect(),
+                    operation,
+                    divisor: divisors[i],
+                    targets: (targets[0], targets[1 % targets.len()]),
+                }
+            })
+            .collect::<Vec<Monkey>>();
+
+        if relieved_rounds_fit(&monkeys) {
+            return monkeys
+                .iter()
+                .enumerate()
+                .map(|(i, m)| {
+                    let operand = m.operation.1.map_or("old".to_string(), |v| v.to_string());
+                    let items = m
+                        .items
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    format!(
+                        "Monkey {}:\n  Starting items: {}\n  Operation: new = old {} {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                        i, items, m.operation.0, operand, m.divisor, m.targets.0, m.targets.1
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n");
+        }
+    }
+}
+
+fn relieved_rounds_fit(monkeys: &[Monkey]) -> bool {
+    let mut items = monkeys.iter().map(|m| m.items.clone()).collect::<Vec<Vec<u64>>>();
+    for _ in 0..20 {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for item in std::mem::take(&mut items[i]) {
+                let operand = monkey.operation.1.unwrap_or(item);
+                let worry = match monkey.operation.0 {
+                    '*' => item.checked_mul(operand),
+                    _ => item.checked_add(operand),
+                };
+                let Some(worry) = worry else {
+                    return false;
+                };
+                let worry = worry / 3;
+                let target = if worry % monkey.divisor == 0 {
+                    monkey.targets.0
+                } else {
+                    monkey.targets.1
+                };
+                items[target].push(worry);
+            }
+        }
+    }
+    true
+}
+
+// Heights grow along the diagonal from S to E, so the untouched staircase
+// along the top row and right column is always climbable. Random pits are
+// sprinkled elsewhere to make the search interesting.
+pub fn heightmap(rng: &mut Rng, size: usize) -> String {
+    let (width, height) = (size.max(9) * 2, size.max(9));
+    let span = width + height - 2;
+    let mut grid = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let level = (x + y) * 25 / span;
+                    let on_staircase = y == 0 || x == width - 1;
+                    let level = if !on_staircase && rng.chance(0.25) {
+                        level.saturating_sub(rng.range_usize(1, 4))
+                    } else {
+                        level
+                    };
+                    (b'a' + level as u8) as char
+                })
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+    grid[0][0] = 'S';
+    grid[height - 1][width - 1] = 'E';
+    render_grid(&grid)
+}
+
+fn packet(rng: &mut Rng, depth: usize) -> String {
+    let len = rng.range(0, if depth == 0 { 6 } else { 4 });
+    let items = (0..len)
+        .map(|_| {
+            if depth < 3 && rng.chance(0.3) {
+                packet(rng, depth + 1)
+            } else {
+                rng.range(0, 11).to_string()
+            }
+        })
+        .collect::<Vec<String>>();
+    format!("[{}]", items.join(","))
+}
+
+// Any chain of one-item lists around a 2 or a 6, like `[2]` or `[[[6]]]`,
+// compares equal to a divider and would leave its place in part 2 ambiguous.
+fn is_divider(packet: &str) -> bool {
+    matches!(packet.replace(['[', ']'], "").as_str(), "2" | "6")
+}
+
+fn non_divider_packet(rng: &mut Rng) -> String {
+    loop {
+        let packet = packet(rng, 0);
+        if !is_divider(&packet) {
+            return packet;
+        }
+    }
+}
+
+pub fn packet_pairs(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{}\n{}\n", non_divider_packet(rng), non_divider_packet(rng)))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Rock paths stay below the source at (500, 0) and spread out sideways. Caves
+// where sand would pile up to the source without ever reaching the abyss are
+// rejected, as part 1 would never finish on them.
+pub fn rock_paths(rng: &mut Rng, size: usize) -> String {
+    let spread = (size as i64).max(10);
+    loop {
+        let paths = (0..size)
+            .map(|_| {
+                let mut x = 500 + rng.range_i64(-spread, spread + 1);
+                let mut y = rng.range_i64(2, spread + 2);
+                let mut points = vec![(x, y)];
+                for i in 0..rng.range(1, 5) {
+                    if i % 2 == 0 {
+                        x += rng.range_i64(-8, 9);
+                    } else {
+                        y = (y + rng.range_i64(-5, 6)).max(2);
+                    }
+                    points.push((x, y));
+                }
+                points
+            })
+            .collect::<Vec<Vec<(i64, i64)>>>();
+
+        if sand_reaches_abyss(&paths) {
+            return paths
+                .iter()
+                .map(|path| {
+                    path.iter()
+                        .map(|(x, y)| format!("{},{}", x, y))
+                        .collect::<Vec<String>>()
+                        .join(" -> ")
+                        + "\n"
+                })
+                .collect();
+        }
+    }
+}
+
+fn sand_reaches_abyss(paths: &[Vec<(i64, i64)>]) -> bool {
+    let mut blocked: HashSet<(i64, i64)> = HashSet::new();
+    for path in paths {
+        for pair in path.windows(2) {
+            let ((x_1, y_1), (x_2, y_2)) = (pair[0], pair[1]);
+            for x in x_1.min(x_2)..=x_1.max(x_2) {
+                for y in y_1.min(y_2)..=y_1.max(y_2) {
+                    blocked.insert((x, y));
+                }
+            }
+        }
+    }
+    let bottom = blocked.iter().map(|p| p.1).max().unwrap_or(0);
+
+    while !blocked.contains(&(500, 0)) {
+        let (mut x, mut y) = (500, 0);
+        loop {
+            if y > bottom {
+                return true;
+            }
+            match [x, x - 1, x + 1].iter().find(|n_x| !blocked.contains(&(**n_x, y + 1))) {
+                Some(n_x) => (x, y) = (*n_x, y + 1),
+                None => break,
+            }
+        }
+        blocked.insert((x, y));
+    }
+    false
+}
+
+fn sensor_line(rng: &mut Rng, sensor: (i64, i64), radius: i64) -> String {
+    let d_x = rng.range_i64(0, radius + 1);
+    let d_y = radius - d_x;
+    let beacon = (
+        sensor.0 + if rng.chance(0.5) { d_x } else { -d_x },
+        sensor.1 + if rng.chance(0.5) { d_y } else { -d_y },
+    );
+    format!(
+        "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+        sensor.0, sensor.1, beacon.0, beacon.1
+    )
+}
+
+// A hidden distress beacon is picked first and every sensor's radius stays
+// short of it, so it always remains uncovered. Four sensors sit diagonally
+// off it, at least `bound` away on each axis, with their ranges reaching the
+// cells next to it; between them they cover the rest of 0..=bound, so the
+// hidden beacon is the only gap. Random sensors are mixed in around them.
+pub fn sensors(rng: &mut Rng, size: usize) -> String {
+    let bound = (size as i64 * 1000).max(20);
+    let hidden = (rng.range_i64(0, bound + 1), rng.range_i64(0, bound + 1));
+    let mut lines = vec![];
+    for (s_x, s_y) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let offset = rng.range_i64(bound, bound * 2);
+        let sensor = (hidden.0 + s_x * offset, hidden.1 + s_y * offset);
+        lines.push(sensor_line(rng, sensor, 2 * offset - 1));
+    }
+    while lines.len() < size.max(1) + 4 {
+        let sensor = (rng.range_i64(0, bound + 1), rng.range_i64(0, bound + 1));
+        let to_hidden = (sensor.0 - hidden.0).abs() + (sensor.1 - hidden.1).abs();
+        if to_hidden < 2 {
+            continue;
+        }
+        let radius = rng.range_i64(1, to_hidden);
+        lines.push(sensor_line(rng, sensor, radius));
+    }
+    rng.shuffle(&mut lines);
+    lines.concat()
+}
+
+pub fn valves(rng: &mut Rng, size: usize) -> String {
+    let count = size.clamp(2, 26 * 26);
+    let mut names = (0..26 * 26)
+        .map(|i| format!("{}{}", (b'A' + (i / 26) as u8) as char, (b'A' + (i % 26) as u8) as char))
+        .filter(|n| n != "AA")
+        .collect::<Vec<String>>();
+    rng.shuffle(&mut names);
+    names.truncate(count - 1);
+    names.insert(0, "AA".to_string());
+
+    let mut edges: Vec<HashSet<usize>> = vec![HashSet::new(); count];
+    for i in 1..count {
+        let j = rng.range_usize(0, i);
+        edges[i].insert(j);
+        edges[j].insert(i);
+    }
+    for _ in 0..count / 3 {
+        let (a, b) = (rng.range_usize(0, count), rng.range_usize(0, count));
+        if a != b {
+            edges[a].insert(b);
+            edges[b].insert(a);
+        }
+    }
+
+    (0..count)
+        .map(|i| {
+            let rate = if i > 0 && rng.chance(0.35) {
+                rng.range(1, 26)
+            } else {
+                0
+            };
+            let mut tunnels = edges[i].iter().copied().collect::<Vec<usize>>();
+            tunnels.sort();
+            let tunnels = tunnels
+                .iter()
+                .map(|t| names[*t].clone())
+                .collect::<Vec<String>>();
+            if tunnels.len() == 1 {
+                format!(
+                    "Valve {} has flow rate={}; tunnel leads to valve {}\n",
+                    names[i], rate, tunnels[0]
+                )
+            } else {
+                format!(
+                    "Valve {} has flow rate={}; tunnels lead to valves {}\n",
+                    names[i],
+                    rate,
+                    tunnels.join(", ")
+                )
+            }
+        })
+        .collect()
+}
+
+pub fn jet_pattern(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| *rng.pick(&['<', '>']))
+        .collect::<String>()
+        + "\n"
+}
+
+// A random walk of blobs, which leaves enclosed air pockets now and then.
+pub fn lava_cubes(rng: &mut Rng, size: usize) -> String {
+    let bound = (size as f64).cbrt().ceil() as i64 * 2 + 2;
+    let mut cubes: HashSet<(i64, i64, i64)> = HashSet::new();
+    let mut ordered = vec![];
+    let mut current = (bound / 2, bound / 2, bound / 2);
+    while cubes.len() < size {
+        if cubes.insert(current) {
+            ordered.push(current);
+        }
+        let axis = rng.range(0, 3);
+        let step = if rng.chance(0.5) { 1 } else { -1 };
+        let moved = match axis {
+            0 => (current.0 + step, current.1, current.2),
+            1 => (current.0, current.1 + step, current.2),
+            _ => (current.0, current.1, current.2 + step),
+        };
+        if [moved.0, moved.1, moved.2].iter().all(|c| (0..bound).contains(c)) {
+            current = moved;
+        }
+    }
+    rng.shuffle(&mut ordered);
+    ordered
+        .iter()
+        .map(|(x, y, z)| format!("{},{},{}\n", x, y, z))
+        .collect()
+}
+
+pub fn blueprints(rng: &mut Rng, size: usize) -> String {
+    (1..=size)
+        .map(|i| {
+            format!(
+                "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.\n",
+                i,
+                rng.range(2, 5),
+                rng.range(2, 5),
+                rng.range(2, 5),
+                rng.range(4, 20),
+                rng.range(2, 5),
+                rng.range(5, 20)
+            )
+        })
+        .collect()
+}
+
+// Exactly one zero, as the grove coordinates are counted from it.
+pub fn encrypted_file(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let zero = rng.range_usize(0, size);
+    (0..size)
+        .map(|i| {
+            let value = if i == zero {
+                0
+            } else {
+                let value = rng.range_i64(1, 10000);
+                if rng.chance(0.5) { value } else { -value }
+            };
+            format!("{}\n", value)
+        })
+        .collect()
+}
+
+// Built top-down from known values so every division is exact. `humn` sits in
+// the left subtree of `root` and only behind +, - and *, so part 2 always has
+// an integer answer.
+pub fn monkey_math(rng: &mut Rng, size: usize) -> String {
+    struct Builder<'a> {
+        rng: &'a mut Rng,
+        lines: Vec<String>,
+        next_name: usize,
+    }
+
+    impl Builder<'_> {
+        fn name(&mut self) -> String {
+            let mut n = self.next_name;
+            self.next_name += 1;
+            let mut name = String::new();
+            for _ in 0..4 {
+                name.push((b'a' + (n % 26) as u8) as char);
+                n /= 26;
+            }
+            if name == "humn" || name == "root" {
+                return self.name();
+            }
+            name
+        }
+
+        fn node(&mut self, name: String, value: i64, depth: usize, with_human: bool) {
+            if depth == 0 {
+                if with_human {
+                    self.lines.push(format!("humn: {}", self.rng.range(1, 100)));
+                } else {
+                    self.lines.push(format!("{}: {}", name, value));
+                }
+                return;
+            }
+
+            let (left, right) = (self.name(), self.name());
+            let human_left = self.rng.chance(0.5);
+            let (left_value, operator, right_value) = match self.rng.range(0, 4) {
+                0 => {
+                    let b = self.rng.range_i64(1, 20);
+                    (value - b, '+', b)
+                }
+                1 => {
+                    let b = self.rng.range_i64(1, 20);
+                    (value + b, '-', b)
+                }
+                2 if value != 0 && value % 2 == 0 => (value / 2, '*', 2),
+                _ if !with_human => {
+                    let b = self.rng.range_i64(2, 6);
+                    (value * b, '/', b)
+                }
+                _ => {
+                    let b = self.rng.range_i64(1, 20);
+                    (value - b, '+', b)
+                }
+            };
+            let (human_left, human_right) = (with_human && human_left, with_human && !human_left);
+            let left_name = if human_left && depth == 1 { "humn".to_string() } else { left };
+            let right_name = if human_right && depth == 1 { "humn".to_string() } else { right };
+
+            self.lines.push(format!("{}: {} {} {}", name, left_name, operator, right_name));
+            self.node(left_name, left_value, depth - 1, human_left);
+            self.node(right_name, right_value, depth - 1, human_right);
+        }
+    }
+
+    let depth = (size.max(4) as f64).log2().ceil() as usize;
+    let mut builder = Builder {
+        rng,
+        lines: vec![],
+        next_name: 0,
+    };
+    let value = builder.rng.range_i64(100, 10000);
+    let (left, right) = (builder.name(), builder.name());
+    builder.lines.push(format!("root: {} + {}", left, right));
+    builder.node(left, value, depth, true);
+    builder.node(right, value, depth, false);
+
+    let mut lines = builder.lines;
+    builder.rng.shuffle(&mut lines);
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,196 @@
+use super::{outline, render_grid, spanning_tree};
+use crate::rng::Rng;
+
+const ALMANAC_MAPS: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+// Source ranges within a map never overlap, like in the real almanacs.
+pub fn almanac(rng: &mut Rng, size: usize) -> String {
+    let limit = 1u64 << 32;
+    let seeds = (0..size.max(1))
+        .map(|_| {
+            let start = rng.range(0, limit / 2);
+            format!("{} {}", start, rng.range(1, limit / 64))
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    let maps = ALMANAC_MAPS
+        .iter()
+        .map(|name| {
+            let mut cuts = (0..size.max(1) * 2)
+                .map(|_| rng.range(0, limit))
+                .collect::<Vec<u64>>();
+            cuts.sort();
+            cuts.dedup();
+            let lines = cuts
+                .chunks_exact(2)
+                .map(|pair| {
+                    let length = pair[1] - pair[0];
+                    format!("{} {} {}\n", rng.range(0, limit - length), pair[0], length)
+                })
+                .collect::<String>();
+            format!("{} map:\n{}", name, lines)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!("seeds: {}\n\n{}", seeds, maps)
+}
+
+pub fn boat_races(rng: &mut Rng, size: usize) -> String {
+    let races = (0..size.clamp(1, 4))
+        .map(|_| {
+            let time = rng.range(10, 100);
+            let best = (time / 2) * (time - time / 2);
+            (time, rng.range(time, best))
+        })
+        .collect::<Vec<(u64, u64)>>();
+
+    let times = races
+        .iter()
+        .map(|r| format!("{:>5}", r.0))
+        .collect::<String>();
+    let distances = races
+        .iter()
+        .map(|r| format!("{:>5}", r.1))
+        .collect::<String>();
+    format!("Time:    {}\nDistance:{}\n", times, distances)
+}
+
+// The loop is the outline of a random spanning tree drawn at double scale, so
+// it encloses some tiles, surrounded by junk pipes that aren't connected to it.
+pub fn pipe_loop(rng: &mut Rng, size: usize) -> String {
+    let nodes = (size / 4).max(2);
+    let tree = spanning_tree(rng, nodes, nodes);
+    let scaled = tree
+        .iter()
+        .flat_map(|row| {
+            let row = row.iter().flat_map(|c| [*c, *c]).collect::<Vec<bool>>();
+            [row.clone(), row]
+        })
+        .collect::<Vec<Vec<bool>>>();
+    let points = outline(&scaled);
+
+    let side = scaled.len() + 1;
+    let junk = ['|', '-', 'L', 'J', '7', 'F', '.', '.', '.'];
+    let mut grid = (0..side)
+        .map(|_| (0..side).map(|_| *rng.pick(&junk)).collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+
+    for i in 0..points.len() {
+        let previous = points[(i + points.len() - 1) % points.len()];
+        let (x, y) = points[i];
+        let next = points[(i + 1) % points.len()];
+        let mut exits = [previous, next].map(|(n_x, n_y)| {
+            if n_y < y {
+                'N'
+            } else if n_y > y {
+                'S'
+            } else if n_x > x {
+                'E'
+            } else {
+                'W'
+            }
+        });
+        exits.sort();
+        grid[y][x] = match exits {
+            ['N', 'S'] => '|',
+            ['E', 'W'] => '-',
+            ['E', 'N'] => 'L',
+            ['N', 'W'] => 'J',
+            ['S', 'W'] => '7',
+            ['E', 'S'] => 'F',
+            _ => unreachable!(),
+        };
+    }
+
+    let (s_x, s_y) = *rng.pick(&points);
+    grid[s_y][s_x] = 'S';
+    render_grid(&grid)
+}
+
+pub fn galaxies(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let empty_rows = (0..size).map(|_| rng.chance(0.1)).collect::<Vec<bool>>();
+    let empty_columns = (0..size).map(|_| rng.chance(0.1)).collect::<Vec<bool>>();
+    let grid = (0..size)
+        .map(|y| {
+            (0..size)
+                .map(|x| {
+                    if !empty_rows[y] && !empty_columns[x] && rng.chance(0.05) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+    render_grid(&grid)
+}
+
+// Workflows form a tree rooted at `in`, so every part terminates in A or R.
+pub fn workflows(rng: &mut Rng, size: usize) -> String {
+    fn name(index: usize) -> String {
+        let mut n = index;
+        let mut name = String::new();
+        for _ in 0..3 {
+            name.push((b'a' + (n % 26) as u8) as char);
+            n /= 26;
+        }
+        name
+    }
+
+    let mut lines = vec![];
+    let mut queue = vec!["in".to_string()];
+    let mut created = 1;
+    while let Some(workflow) = queue.pop() {
+        let mut destination = |rng: &mut Rng, queue: &mut Vec<String>| {
+            if created < size && rng.chance(0.6) {
+                let child = name(created);
+                created += 1;
+                queue.push(child.clone());
+                child
+            } else {
+                rng.pick(&["A", "R"]).to_string()
+            }
+        };
+
+        let rules = (0..rng.range(1, 4))
+            .map(|_| {
+                format!(
+                    "{}{}{}:{}",
+                    rng.pick(&['x', 'm', 'a', 's']),
+                    rng.pick(&['<', '>']),
+                    rng.range(1, 4000),
+                    destination(rng, &mut queue)
+                )
+            })
+            .collect::<Vec<String>>();
+        let fallback = destination(rng, &mut queue);
+        lines.push(format!("{}{{{},{}}}", workflow, rules.join(","), fallback));
+    }
+    rng.shuffle(&mut lines);
+
+    let parts = (0..size.max(1))
+        .map(|_| {
+            format!(
+                "{{x={},m={},a={},s={}}}\n",
+                rng.range(1, 4001),
+                rng.range(1, 4001),
+                rng.range(1, 4001),
+                rng.range(1, 4001)
+            )
+        })
+        .collect::<String>();
+
+    format!("{}\n\n{}", lines.join("\n"), parts)
+}
@@ -0,0 +1,442 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::{render_grid, spanning_tree};
+use crate::rng::Rng;
+
+pub fn location_lists(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| format!("{}   {}\n", rng.range(10000, 100000), rng.range(10000, 100000)))
+        .collect()
+}
+
+// Mostly gentle slopes, with an occasional bad level so that both the strict
+// and the dampened rules have work to do.
+pub fn reports(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let direction = if rng.chance(0.5) { 1 } else { -1 };
+            let mut level = rng.range_i64(20, 80);
+            let mut levels = vec![level];
+            for _ in 0..rng.range(4, 9) {
+                level += if rng.chance(0.1) {
+                    rng.range_i64(-4, 5)
+                } else {
+                    direction * rng.range_i64(1, 4)
+                };
+                levels.push(level.max(1));
+            }
+            levels
+                .iter()
+                .map(|l| l.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+                + "\n"
+        })
+        .collect()
+}
+
+pub fn guard_map(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let mut grid = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.chance(0.08) { '#' } else { '.' })
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+    let (x, y) = (rng.range_usize(0, size), rng.range_usize(0, size));
+    grid[y][x] = '^';
+    render_grid(&grid)
+}
+
+// Targets are built from the operands with random operators, and every other
+// one is nudged so that some equations are unsolvable.
+pub fn calibrations(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let operands = (0..rng.range(2, 8))
+                .map(|_| rng.range(1, 1000))
+                .collect::<Vec<u64>>();
+            let mut target = operands[0];
+            for operand in &operands[1..] {
+                target = match rng.range(0, 3) {
+                    0 => target + operand,
+                    1 => target.saturating_mul(*operand),
+                    _ => format!("{}{}", target, operand).parse().unwrap_or(u64::MAX),
+                }
+                .min(u64::MAX / 1000);
+            }
+            if rng.chance(0.5) {
+                target += 1;
+            }
+            let operands = operands
+                .iter()
+                .map(|o| o.to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+            format!("{}: {}\n", target, operands)
+        })
+        .collect()
+}
+
+// Besides `size` random robots, a framed tree is planted: its robots line up
+// at a random second, found by running their moves backwards.
+pub fn robots(rng: &mut Rng, size: usize) -> String {
+    let (width, height) = (101, 103);
+    let at = rng.range_i64(200, width * height);
+    let (left, top) = (rng.range_i64(0, width - 31), rng.range_i64(0, height - 33));
+
+    let mut picture = vec![];
+    for y in 0..33i64 {
+        for x in 0..31i64 {
+            let frame = x == 0 || y == 0 || x == 30 || y == 32;
+            let crown = (2..26).contains(&y) && (x - 15).abs() <= (y - 2) / 2;
+            let trunk = (26..30).contains(&y) && (13..=17).contains(&x);
+            if frame || crown || trunk {
+                picture.push((left + x, top + y));
+            }
+        }
+    }
+
+    let mut lines = picture
+        .iter()
+        .map(|(x, y)| {
+            let velocity = (rng.range_i64(-100, 101), rng.range_i64(-100, 101));
+            let start = (
+                (x - velocity.0 * at).rem_euclid(width),
+                (y - velocity.1 * at).rem_euclid(height),
+            );
+            format!("p={},{} v={},{}\n", start.0, start.1, velocity.0, velocity.1)
+        })
+        .collect::<Vec<String>>();
+    lines.extend((0..size).map(|_| {
+        format!(
+            "p={},{} v={},{}\n",
+            rng.range_i64(0, width),
+            rng.range_i64(0, height),
+            rng.range_i64(-100, 101),
+            rng.range_i64(-100, 101)
+        )
+    }));
+    rng.shuffle(&mut lines);
+    lines.concat()
+}
+
+pub fn warehouse(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(4);
+    let mut grid = (0..size)
+        .map(|y| {
+            (0..size)
+                .map(|x| {
+                    if x == 0 || y == 0 || x == size - 1 || y == size - 1 || rng.chance(0.05) {
+                        '#'
+                    } else if rng.chance(0.2) {
+                        'O'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+    let (x, y) = (rng.range_usize(1, size - 1), rng.range_usize(1, size - 1));
+    grid[y][x] = '@';
+
+    let moves = (0..size * size)
+        .map(|_| *rng.pick(&['<', '>', '^', 'v']))
+        .collect::<Vec<char>>();
+    let moves = moves
+        .chunks(70)
+        .map(|line| line.iter().collect::<String>() + "\n")
+        .collect::<String>();
+
+    format!("{}\n{}", render_grid(&grid), moves)
+}
+
+fn maze(rng: &mut Rng, size: usize) -> Vec<Vec<char>> {
+    let nodes = (size / 2).max(2);
+    let tree = spanning_tree(rng, nodes, nodes);
+    let side = 2 * nodes + 1;
+    let mut grid = vec![vec!['#'; side]; side];
+    for (y, row) in tree.iter().enumerate() {
+        for (x, open) in row.iter().enumerate() {
+            if *open {
+                grid[y + 1][x + 1] = '.';
+            }
+        }
+    }
+    grid
+}
+
+// A perfect maze with a few extra walls knocked out, so there are several
+// equally good routes to compare.
+pub fn reindeer_maze(rng: &mut Rng, size: usize) -> String {
+    let mut grid = maze(rng, size);
+    let side = grid.len();
+    for (y, row) in grid.iter_mut().enumerate().take(side - 1).skip(1) {
+        for (x, field) in row.iter_mut().enumerate().take(side - 1).skip(1) {
+            let between_nodes = (x % 2 == 0) != (y % 2 == 0);
+            if *field == '#' && between_nodes && rng.chance(0.1) {
+                *field = '.';
+            }
+        }
+    }
+    grid[side - 2][1] = 'S';
+    grid[1][side - 2] = 'E';
+    render_grid(&grid)
+}
+
+// Only the maze path between S and E is kept, so the track never branches.
+pub fn racetrack(rng: &mut Rng, size: usize) -> String {
+    let grid = maze(rng, size);
+    let side = grid.len();
+    let (start, end) = ((1, side - 2), (side - 2, 1));
+
+    let mut previous: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some((x, y)) = queue.pop_front() {
+        if (x, y) == end {
+            break;
+        }
+        for (n_x, n_y) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+            if grid[n_y][n_x] == '.' && !previous.contains_key(&(n_x, n_y)) && (n_x, n_y) != start
+            {
+                previous.insert((n_x, n_y), (x, y));
+                queue.push_back((n_x, n_y));
+            }
+        }
+    }
+
+    let mut track = vec![vec!['#'; side]; side];
+    let mut current = end;
+    while current != start {
+        track[current.1][current.0] = '.';
+        current = previous[&current];
+    }
+    track[start.1][start.0] = 'S';
+    track[end.1][end.0] = 'E';
+    render_grid(&track)
+}
+
+fn run_three_bit(program: &[u64], mut a: u64) -> Vec<u64> {
+    let (mut b, mut c) = (0, 0);
+    let mut position = 0;
+    let mut output = vec![];
+    while position + 1 < program.len() {
+        let operand = program[position + 1];
+        let combo = match operand {
+            4 => a,
+            5 => b,
+            6 => c,
+            _ => operand,
+        };
+        match program[position] {
+            0 => a = a.checked_shr(combo as u32).unwrap_or(0),
+            1 => b ^= operand,
+            2 => b = combo % 8,
+            3 if a != 0 => {
+                position = operand as usize;
+                continue;
+            }
+            4 => b ^= c,
+            5 => output.push(combo % 8),
+            6 => b = a.checked_shr(combo as u32).unwrap_or(0),
+            7 => c = a.checked_shr(combo as u32).unwrap_or(0),
+            _ => {}
+        }
+        position += 2;
+    }
+    output
+}
+
+fn quine_exists(program: &[u64]) -> bool {
+    let mut queue = VecDeque::from([(0u64, 0usize)]);
+    while let Some((a, digit)) = queue.pop_front() {
+        if queue.len() > 10_000 {
+            return false;
+        }
+        let target = program[program.len() - digit - 1];
+        for i in 0..8 {
+            let candidate = (a << 3) | i;
+            let output = run_three_bit(program, candidate);
+            if output.first() != Some(&target) {
+                continue;
+            }
+            if digit == program.len() - 1 {
+                if output == program {
+                    return true;
+                }
+            } else {
+                queue.push_back((candidate, digit + 1));
+            }
+        }
+    }
+    false
+}
+
+// The usual shape: shift A by three bits each round, scramble the low bits
+// through B and C, output B and loop while A is non-zero. Parameters are
+// redrawn until the program can output itself, so part 2 always has an answer.
+// Operand 7 is reserved, so it is never drawn.
+pub fn three_bit_program(rng: &mut Rng, size: usize) -> String {
+    loop {
+        let program = vec![
+            2,
+            4,
+            1,
+            rng.range(0, 7),
+            7,
+            5,
+            1,
+            rng.range(0, 7),
+            4,
+            rng.range(0, 7),
+            0,
+            3,
+            5,
+            5,
+            3,
+            0,
+        ];
+        if !quine_exists(&program) {
+            continue;
+        }
+
+        let digits = (size as u32).clamp(1, 20);
+        let a = rng.range(1 << (3 * (digits - 1)), 1 << (3 * digits));
+        let program = program
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        return format!(
+            "Register A: {}\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n",
+            a, program
+        );
+    }
+}
+
+// (left, operator, right, output, bit)
+type AdderGate = (String, &'static str, String, String, usize);
+
+fn swap_outputs(gates: &mut [AdderGate], a: usize, b: usize) {
+    let output_a = gates[a].3.clone();
+    gates[a].3 = gates[b].3.clone();
+    gates[b].3 = output_a;
+}
+
+fn is_acyclic(gates: &[AdderGate]) -> bool {
+    let mut known: HashSet<&str> = HashSet::new();
+    let mut remaining = gates.iter().collect::<Vec<&AdderGate>>();
+    while !remaining.is_empty() {
+        let before = remaining.len();
+        remaining.retain(|(left, _, right, output, _)| {
+            let ready = [left, right]
+                .iter()
+                .all(|w| w.starts_with(['x', 'y']) || known.contains(w.as_str()));
+            if ready {
+                known.insert(output);
+            }
+            !ready
+        });
+        if remaining.len() == before {
+            return false;
+        }
+    }
+    true
+}
+
+// A ripple-carry adder over `size` bits with up to four pairs of gate outputs
+// swapped. Like in the real puzzle, each swap stays inside one bit's full adder,
+// the swapped bits are well apart and the circuit never loops.
+pub fn adder(rng: &mut Rng, size: usize) -> String {
+    adder_with_swaps(rng, size).0
+}
+
+// The adder along with the swapped wires, sorted the way part 2 wants them.
+pub fn adder_with_swaps(rng: &mut Rng, size: usize) -> (String, Vec<String>) {
+    let bits = size.clamp(8, 99);
+    let mut names = (0..26 * 26 * 26)
+        .map(|i| {
+            [i / 676, (i / 26) % 26, i % 26]
+                .iter()
+                .map(|c| (b'a' + *c as u8) as char)
+                .collect::<String>()
+        })
+        .filter(|n| !n.starts_with(['x', 'y', 'z']))
+        .collect::<Vec<String>>();
+    rng.shuffle(&mut names);
+    let mut names = names.into_iter();
+
+    let mut gates: Vec<AdderGate> = vec![];
+    let mut carry = names.next().unwrap();
+    gates.push(("x00".into(), "XOR", "y00".into(), "z00".into(), 0));
+    gates.push(("x00".into(), "AND", "y00".into(), carry.clone(), 0));
+    for i in 1..bits {
+        let (x, y, z) = (format!("x{:0>2}", i), format!("y{:0>2}", i), format!("z{:0>2}", i));
+        let half_sum = names.next().unwrap();
+        let half_carry = names.next().unwrap();
+        let full_carry = names.next().unwrap();
+        let next_carry = if i == bits - 1 {
+            format!("z{:0>2}", bits)
+        } else {
+            names.next().unwrap()
+        };
+        gates.push((x.clone(), "XOR", y.clone(), half_sum.clone(), i));
+        gates.push((x, "AND", y, half_carry.clone(), i));
+        gates.push((half_sum.clone(), "XOR", carry.clone(), z, i));
+        gates.push((half_sum, "AND", carry, full_carry.clone(), i));
+        gates.push((half_carry, "OR", full_carry, next_carry.clone(), i));
+        carry = next_carry;
+    }
+
+    let swaps = ((bits - 2) / 6).min(4);
+    let mut swapped = vec![];
+    for k in 0..swaps {
+        let stride = (bits - 2) / swaps;
+        let bit = 1 + k * stride + rng.range_usize(0, stride - 5);
+        let in_bit = (0..gates.len())
+            .filter(|g| gates[*g].4 == bit)
+            .collect::<Vec<usize>>();
+        loop {
+            let (a, b) = (*rng.pick(&in_bit), *rng.pick(&in_bit));
+            // Both ANDs only feed the OR, so swapping them changes nothing.
+            let (first, second) = (a.min(b) - in_bit[0], a.max(b) - in_bit[0]);
+            if a == b || (first, second) == (1, 3) {
+                continue;
+            }
+            swap_outputs(&mut gates, a, b);
+            if is_acyclic(&gates) {
+                swapped.push(gates[a].3.clone());
+                swapped.push(gates[b].3.clone());
+                break;
+            }
+            swap_outputs(&mut gates, a, b);
+        }
+    }
+
+    let mut registers = vec![];
+    for register in ['x', 'y'] {
+        for i in 0..bits {
+            registers.push(format!("{}{:0>2}: {}", register, i, rng.range(0, 2)));
+        }
+    }
+
+    let mut lines = gates
+        .iter()
+        .map(|(left, operator, right, output, _)| {
+            if rng.chance(0.5) {
+                format!("{} {} {} -> {}", left, operator, right, output)
+            } else {
+                format!("{} {} {} -> {}", right, operator, left, output)
+            }
+        })
+        .collect::<Vec<String>>();
+    rng.shuffle(&mut lines);
+
+    swapped.sort();
+    (
+        format!("{}\n\n{}\n", registers.join("\n"), lines.join("\n")),
+        swapped,
+    )
+}
@@ -0,0 +1,115 @@
+use super::{outline, spanning_tree};
+use crate::rng::Rng;
+
+pub fn battery_banks(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..100)
+                .map(|_| (b'0' + rng.range(1, 10) as u8) as char)
+                .collect::<String>()
+                + "\n"
+        })
+        .collect()
+}
+
+// The outline of a random spanning tree keeps its shape under any increasing
+// remapping of the axes, so corners are spread out over random gaps to get a
+// large rectilinear polygon.
+pub fn red_tiles(rng: &mut Rng, size: usize) -> String {
+    let nodes = (size / 4).max(2);
+    let tree = spanning_tree(rng, nodes, nodes);
+    let points = outline(&tree);
+
+    let axis = |rng: &mut Rng| {
+        let mut position = rng.range(0, 1000);
+        (0..2 * nodes)
+            .map(|_| {
+                position += rng.range(1, 2000);
+                position
+            })
+            .collect::<Vec<u64>>()
+    };
+    let (xs, ys) = (axis(rng), axis(rng));
+
+    (0..points.len())
+        .filter(|i| {
+            let previous = points[(i + points.len() - 1) % points.len()];
+            let next = points[(i + 1) % points.len()];
+            previous.0 != next.0 && previous.1 != next.1
+        })
+        .map(|i| format!("{},{}\n", xs[points[i].0], ys[points[i].1]))
+        .collect()
+}
+
+// Six 3x3 shapes, then three kinds of regions like in the real input: roomy
+// ones where every present gets its own 3x3 slot, overfull ones with more
+// present area than floor, and small tight ones that need an actual search.
+pub fn present_regions(rng: &mut Rng, size: usize) -> String {
+    let shapes = (0..6)
+        .map(|i| {
+            let mut cells = [true; 9];
+            for _ in 0..rng.range(2, 5) {
+                cells[rng.range_usize(0, 9)] = false;
+            }
+            cells[4] = true;
+            let rows = cells
+                .chunks(3)
+                .map(|row| {
+                    row.iter()
+                        .map(|c| if *c { '#' } else { '.' })
+                        .collect::<String>()
+                        + "\n"
+                })
+                .collect::<String>();
+            (format!("{}:\n{}", i, rows), cells.iter().filter(|c| **c).count())
+        })
+        .collect::<Vec<(String, usize)>>();
+
+    let regions = (0..size)
+        .map(|_| {
+            let kind = rng.range(0, 3);
+            let (width, height) = if kind == 2 {
+                (rng.range_usize(4, 7), rng.range_usize(4, 7))
+            } else {
+                (rng.range_usize(12, 51), rng.range_usize(12, 51))
+            };
+            let slots = (width / 3) * (height / 3);
+            let budget = match kind {
+                0 => slots * 9,
+                1 => width * height * rng.range_usize(101, 120) / 100,
+                _ => width * height * rng.range_usize(50, 90) / 100,
+            };
+
+            let mut counts = [0; 6];
+            let (mut pieces, mut area) = (0, 0);
+            loop {
+                let shape = rng.range_usize(0, 6);
+                if kind == 0 && pieces == slots {
+                    break;
+                }
+                if kind != 1 && area + shapes[shape].1 > budget {
+                    break;
+                }
+                if kind == 1 && area > budget {
+                    break;
+                }
+                pieces += 1;
+                area += shapes[shape].1;
+                counts[shape] += 1;
+            }
+            let counts = counts
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>()
+                .join(" ");
+            format!("{}x{}: {}\n", width, height, counts)
+        })
+        .collect::<String>();
+
+    let shapes = shapes
+        .into_iter()
+        .map(|(s, _)| s)
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!("{}\n{}", shapes, regions)
+}
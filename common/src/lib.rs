@@ -0,0 +1,27 @@
+use std::env;
+use std::str::FromStr;
+
+pub mod animate;
+pub mod diff;
+pub mod generators;
+pub mod ocr;
+pub mod render;
+pub mod rng;
+
+// Value following `flag` on the command line, like `--seed 42`.
+pub fn arg_value(flag: &str) -> Option<String> {
+    let args = env::args().collect::<Vec<String>>();
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+// The value following `flag` parsed as a `T`, like `--rounds 20`. Panics on
+// anything that doesn't parse rather than falling back to a default.
+pub fn parsed_arg<T: FromStr>(flag: &str) -> Option<T> {
+    arg_value(flag).map(|v| {
+        v.parse()
+            .unwrap_or_else(|_| panic!("Invalid value {:?} for {}", v, flag))
+    })
+}
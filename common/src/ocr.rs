@@ -0,0 +1,310 @@
+// Reads the capital letters some puzzles draw on a grid of lit pixels. There
+// are two fonts: the 4x6 one most CRT and dot-matrix puzzles use, and the 6x10
+// one from the 2018 star message. Letters are separated by blank columns.
+use std::sync::LazyLock;
+
+const SMALL: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE: &[(char, [&str; 10])] = &[
+    (
+        'A',
+        [
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        [
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        [
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        [
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        [
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        [
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        [
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        [
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+// A glyph that isn't in the font, with the grid column it starts at and its
+// rows drawn with `#` and `.`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unknown {
+    pub index: usize,
+    pub column: usize,
+    pub rows: Vec<String>,
+}
+
+// The recognised text, with a `?` in place of every unknown glyph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reading {
+    pub text: String,
+    pub unknown: Vec<Unknown>,
+}
+
+impl Reading {
+    pub fn is_complete(&self) -> bool {
+        self.unknown.is_empty()
+    }
+}
+
+fn draw(rows: &[Vec<bool>], columns: std::ops::Range<usize>) -> Vec<String> {
+    rows.iter()
+        .map(|row| {
+            columns
+                .clone()
+                .map(|x| if row.get(x) == Some(&true) { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+// Font glyphs get the same treatment as the ones read from the grid: outer
+// blank columns dropped, so `I` with its empty left column still matches.
+fn trimmed(glyph: &[&str]) -> Vec<String> {
+    let rows = glyph
+        .iter()
+        .map(|row| row.chars().map(|c| c == '#').collect())
+        .collect::<Vec<Vec<bool>>>();
+    let width = rows[0].len();
+    let lit = |x: usize| rows.iter().any(|row| row[x]);
+    let start = (0..width).find(|x| lit(*x)).unwrap_or(0);
+    let end = (0..width).rev().find(|x| lit(*x)).map_or(0, |x| x + 1);
+    draw(&rows, start..end)
+}
+
+type Font = Vec<(char, Vec<String>)>;
+
+fn trimmed_font<const N: usize>(font: &[(char, [&str; N])]) -> Font {
+    font.iter().map(|(c, glyph)| (*c, trimmed(glyph))).collect()
+}
+
+static SMALL_FONT: LazyLock<Font> = LazyLock::new(|| trimmed_font(SMALL));
+static LARGE_FONT: LazyLock<Font> = LazyLock::new(|| trimmed_font(LARGE));
+
+fn lookup(rows: &[String]) -> Option<char> {
+    let font = match rows.len() {
+        6 => &SMALL_FONT,
+        10 => &LARGE_FONT,
+        _ => return None,
+    };
+    font.iter()
+        .find(|(_, glyph)| glyph == rows)
+        .map(|(c, _)| *c)
+}
+
+// Blank rows around the text are ignored, so the letters can sit anywhere in a
+// bigger picture. Any other height only gives unknown glyphs.
+pub fn read(grid: &[Vec<bool>]) -> Reading {
+    let lit_row = |row: &Vec<bool>| row.iter().any(|p| *p);
+    let top = grid.iter().position(lit_row).unwrap_or(0);
+    let bottom = grid.iter().rposition(lit_row).map_or(0, |y| y + 1);
+    let rows = &grid[top..bottom.max(top)];
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+
+    let mut reading = Reading {
+        text: String::new(),
+        unknown: vec![],
+    };
+    let mut x = 0;
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+
+        let glyph = draw(rows, start..x);
+        match lookup(&glyph) {
+            Some(c) => reading.text.push(c),
+            None => {
+                reading.unknown.push(Unknown {
+                    index: reading.text.len(),
+                    column: start,
+                    rows: glyph,
+                });
+                reading.text.push('?');
+            }
+        }
+    }
+    reading
+}
+
+// Same as `read`, for grids drawn with characters.
+pub fn read_chars(grid: &[Vec<char>], lit: char) -> Reading {
+    let grid = grid
+        .iter()
+        .map(|row| row.iter().map(|c| *c == lit).collect())
+        .collect::<Vec<Vec<bool>>>();
+    read(&grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|row| row.chars().collect()).collect()
+    }
+
+    #[test]
+    fn reads_the_small_font() {
+        let screen = grid(&[
+            "#..#.####.#....#.....##.",
+            "#..#.#....#....#....#..#",
+            "####.###..#....#....#..#",
+            "#..#.#....#....#....#..#",
+            "#..#.#....#....#....#..#",
+            "#..#.####.####.####..##.",
+        ]);
+        assert_eq!(read_chars(&screen, '#').text, "HELLO");
+    }
+
+    #[test]
+    fn reads_the_large_font_anywhere_in_the_picture() {
+        let sky = grid(&[
+            "................",
+            "..#....#..######",
+            "..##...#.......#",
+            "..##...#.......#",
+            "..#.#..#......#.",
+            "..#.#..#.....#..",
+            "..#..#.#....#...",
+            "..#..#.#...#....",
+            "..#...##..#.....",
+            "..#...##..#.....",
+            "..#....#..######",
+            "................",
+        ]);
+        let reading = read_chars(&sky, '#');
+        assert!(reading.is_complete());
+        assert_eq!(reading.text, "NZ");
+    }
+
+    #[test]
+    fn flags_unknown_glyphs() {
+        let screen = grid(&[
+            "####..#..#",
+            "#.....#..#",
+            "###...#..#",
+            "#.....#..#",
+            "#.....#..#",
+            "####..####",
+        ]);
+        let reading = read_chars(&screen, '#');
+        assert_eq!(reading.text, "E?");
+        assert_eq!(reading.unknown.len(), 1);
+        assert_eq!((reading.unknown[0].index, reading.unknown[0].column), (1, 6));
+    }
+}
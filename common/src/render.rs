@@ -0,0 +1,218 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+
+use crate::arg_value;
+
+pub type Colour = [u8; 3];
+
+// Colour for every character a simulation draws its grid with. Characters
+// without a colour of their own get the background.
+#[derive(Clone, Debug)]
+pub struct Palette {
+    background: Colour,
+    colours: Vec<(char, Colour)>,
+}
+
+impl Palette {
+    pub fn new(background: Colour) -> Self {
+        Palette {
+            background,
+            colours: vec![],
+        }
+    }
+
+    pub fn with(mut self, c: char, colour: Colour) -> Self {
+        self.colours.push((c, colour));
+        self
+    }
+
+    pub fn colour(&self, c: char) -> Colour {
+        self.colours
+            .iter()
+            .find(|(k, _)| *k == c)
+            .map(|(_, colour)| *colour)
+            .unwrap_or(self.background)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    Svg,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Svg => "svg",
+        }
+    }
+}
+
+fn dimensions(grid: &[Vec<char>]) -> (usize, usize) {
+    (
+        grid.iter().map(|row| row.len()).max().unwrap_or(0),
+        grid.len(),
+    )
+}
+
+// Binary PPM, every cell a `scale` x `scale` square. Rows shorter than the
+// longest one are padded with the background.
+pub fn ppm(grid: &[Vec<char>], palette: &Palette, scale: usize) -> Vec<u8> {
+    let (width, height) = dimensions(grid);
+    let mut image = format!("P6\n{} {}\n255\n", width * scale, height * scale).into_bytes();
+    for row in grid {
+        let pixels = (0..width)
+            .flat_map(|x| {
+                let colour = row
+                    .get(x)
+                    .map_or(palette.background, |c| palette.colour(*c));
+                iter::repeat_n(colour, scale)
+            })
+            .flatten()
+            .collect::<Vec<u8>>();
+        for _ in 0..scale {
+            image.extend_from_slice(&pixels);
+        }
+    }
+    image
+}
+
+// One rectangle per run of equal cells in a row, on top of a background
+// rectangle, which keeps mostly empty grids small.
+pub fn svg(grid: &[Vec<char>], palette: &Palette, scale: usize) -> String {
+    let hex = |[r, g, b]: Colour| format!("#{:02x}{:02x}{:02x}", r, g, b);
+    let (width, height) = dimensions(grid);
+    let mut image = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" shape-rendering=\"crispEdges\">\n",
+        width * scale,
+        height * scale
+    );
+    writeln!(
+        image,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        hex(palette.background)
+    )
+    .unwrap();
+
+    for (y, row) in grid.iter().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            let colour = palette.colour(row[x]);
+            let run = row[x..]
+                .iter()
+                .take_while(|c| palette.colour(**c) == colour)
+                .count();
+            if colour != palette.background {
+                writeln!(
+                    image,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    x * scale,
+                    y * scale,
+                    run * scale,
+                    scale,
+                    hex(colour)
+                )
+                .unwrap();
+            }
+            x += run;
+        }
+    }
+
+    image.push_str("</svg>\n");
+    image
+}
+
+// Writes a single image, the format picked from the file extension.
+pub fn save(path: impl AsRef<Path>, grid: &[Vec<char>], palette: &Palette, scale: usize) {
+    let path = path.as_ref();
+    let contents = match path.extension().and_then(|e| e.to_str()) {
+        Some("svg") => svg(grid, palette, scale).into_bytes(),
+        Some("ppm") => ppm(grid, palette, scale),
+        _ => panic!("Unknown image format for {:?}, use .ppm or .svg", path),
+    };
+    fs::write(path, contents).unwrap_or_else(|e| panic!("Unable to write {:?}: {}", path, e));
+}
+
+// Numbered images of a simulation, one per `push`ed step. Long simulations
+// can keep only every n-th step.
+pub struct Frames {
+    directory: PathBuf,
+    format: Format,
+    palette: Palette,
+    scale: usize,
+    every: usize,
+    pushed: usize,
+    written: usize,
+}
+
+impl Frames {
+    pub fn new(directory: impl AsRef<Path>, format: Format, palette: Palette) -> Self {
+        let directory = directory.as_ref().to_path_buf();
+        fs::create_dir_all(&directory)
+            .unwrap_or_else(|e| panic!("Unable to create {:?}: {}", directory, e));
+        Frames {
+            directory,
+            format,
+            palette,
+            scale: 4,
+            every: 1,
+            pushed: 0,
+            written: 0,
+        }
+    }
+
+    // Frames for a day's `--frames DIR` option, with `--svg`, `--scale N` and
+    // `--every N` to tune them. None when no frames were asked for.
+    pub fn from_args(palette: Palette) -> Option<Self> {
+        let directory = arg_value("--frames")?;
+        let number = |flag: &str, default: usize| {
+            arg_value(flag).map_or(default, |v| {
+                v.parse()
+                    .unwrap_or_else(|_| panic!("Invalid number {:?} for {}", v, flag))
+            })
+        };
+        let format = if env::args().any(|a| a == "--svg") {
+            Format::Svg
+        } else {
+            Format::Ppm
+        };
+        Some(
+            Frames::new(directory, format, palette)
+                .scale(number("--scale", 4))
+                .every(number("--every", 1).max(1)),
+        )
+    }
+
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn every(mut self, every: usize) -> Self {
+        self.every = every;
+        self
+    }
+
+    pub fn push(&mut self, grid: &[Vec<char>]) {
+        self.pushed += 1;
+        if !(self.pushed - 1).is_multiple_of(self.every) {
+            return;
+        }
+        let path = self.directory.join(format!(
+            "frame_{:06}.{}",
+            self.written,
+            self.format.extension()
+        ));
+        save(path, grid, &self.palette, self.scale);
+        self.written += 1;
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
+    }
+}
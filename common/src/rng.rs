@@ -0,0 +1,48 @@
+// SplitMix64: tiny, fast and identical on every platform, so a seed always
+// reproduces the same input.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [low, high).
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low < high, "empty range {}..{}", low, high);
+        low + self.next_u64() % (high - low)
+    }
+
+    pub fn range_usize(&mut self, low: usize, high: usize) -> usize {
+        self.range(low as u64, high as u64) as usize
+    }
+
+    pub fn range_i64(&mut self, low: i64, high: i64) -> i64 {
+        low + self.range(0, (high - low) as u64) as i64
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range_usize(0, items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range_usize(0, i + 1));
+        }
+    }
+}
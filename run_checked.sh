@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+# Runs every day that has an input.txt with overflow-checked arithmetic, so any
+# silent wrap-around panics instead of producing a wrong answer.
+set -u
+
+root="$(cd "$(dirname "$0")" && pwd)"
+failed=0
+
+for manifest in "$root"/20*/*/Cargo.toml; do
+    day="$(dirname "$manifest")"
+    [ -f "$day/input.txt" ] || continue
+
+    echo "== ${day#"$root"/}"
+    if ! (cd "$day" && cargo run --quiet --profile checked); then
+        failed=1
+    fi
+done
+
+exit $failed